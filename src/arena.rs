@@ -0,0 +1,398 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::expr::Expression;
+use crate::symbol::Symbol;
+
+/// A handle to a term stored in a `TermArena`.
+///
+/// Terms are hash-consed, so two handles from the same arena are equal if and only if the
+/// terms they refer to are α-equivalent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TermId(u32);
+
+/// A node of a term stored in a `TermArena`.
+///
+/// Bound variables are represented by de Bruijn indices, i.e. the number of functions between
+/// the variable and the function binding it, so that terms equal up to renaming of bound
+/// variables are represented by the same nodes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Term {
+    Bound(u32),
+    Free(Symbol),
+    Function(TermId),
+    Application(TermId, TermId),
+}
+
+/// An arena of hash-consed lambda terms.
+///
+/// Every distinct term is stored exactly once, and subterms are shared between all terms
+/// that contain them. This makes copying a term free and comparing two terms O(1), and the
+/// normal form of a term is remembered so it is only ever computed once.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{church, TermArena};
+///
+/// let mut arena = TermArena::new();
+/// let two = arena.intern(&church::two());
+/// assert_eq!(two, arena.intern(&"λg.λy.g (g y)".parse().unwrap()));
+///
+/// let four = arena.intern(&(church::add() * church::two() * church::two()));
+/// let four = arena.evaluate(four);
+/// assert_eq!(arena.to_expression(four), (church::succ() * church::three()).evaluate());
+/// ```
+#[derive(Debug, Default)]
+pub struct TermArena {
+    terms: Vec<Term>,
+    /// For each term, one more than the largest de Bruijn index that is free in it.
+    loose: Vec<u32>,
+    ids: HashMap<Term, TermId>,
+    normal_forms: HashMap<TermId, TermId>,
+}
+
+impl TermArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of distinct terms in the arena.
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn get(&self, id: TermId) -> &Term {
+        &self.terms[id.0 as usize]
+    }
+
+    /// Add a term to the arena, or find it if it is already there.
+    pub fn add(&mut self, term: Term) -> TermId {
+        if let Some(id) = self.ids.get(&term) {
+            return *id;
+        }
+        let loose = match &term {
+            Term::Bound(index) => index + 1,
+            Term::Free(_) => 0,
+            Term::Function(body) => self.loose(*body).saturating_sub(1),
+            Term::Application(lhs, rhs) => self.loose(*lhs).max(self.loose(*rhs)),
+        };
+        let id = TermId(self.terms.len() as u32);
+        self.terms.push(term.clone());
+        self.loose.push(loose);
+        self.ids.insert(term, id);
+        id
+    }
+
+    pub fn bound(&mut self, index: u32) -> TermId {
+        self.add(Term::Bound(index))
+    }
+
+    pub fn free(&mut self, label: impl Into<Symbol>) -> TermId {
+        self.add(Term::Free(label.into()))
+    }
+
+    pub fn function(&mut self, body: TermId) -> TermId {
+        self.add(Term::Function(body))
+    }
+
+    pub fn application(&mut self, lhs: TermId, rhs: TermId) -> TermId {
+        self.add(Term::Application(lhs, rhs))
+    }
+
+    fn loose(&self, id: TermId) -> u32 {
+        self.loose[id.0 as usize]
+    }
+
+    /// Add an expression to the arena, and return the handle to it.
+    pub fn intern(&mut self, expr: &Expression) -> TermId {
+        enum Task<'a> {
+            Visit(&'a Expression),
+            Function,
+            Application,
+        }
+
+        let mut scope: Vec<Symbol> = Vec::new();
+        let mut tasks = vec![Task::Visit(expr)];
+        let mut results = Vec::new();
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit(Expression::Variable(label)) => {
+                    let id = match scope.iter().rev().position(|param| param == label) {
+                        Some(index) => self.bound(index as u32),
+                        None => self.free(*label),
+                    };
+                    results.push(id);
+                }
+                Task::Visit(Expression::Function(param, body)) => {
+                    scope.push(*param);
+                    tasks.push(Task::Function);
+                    tasks.push(Task::Visit(body));
+                }
+                Task::Visit(Expression::Application(lhs, rhs)) => {
+                    tasks.push(Task::Application);
+                    tasks.push(Task::Visit(rhs));
+                    tasks.push(Task::Visit(lhs));
+                }
+                Task::Function => {
+                    scope.pop();
+                    let body = results.pop().unwrap();
+                    results.push(self.function(body));
+                }
+                Task::Application => {
+                    let rhs = results.pop().unwrap();
+                    let lhs = results.pop().unwrap();
+                    results.push(self.application(lhs, rhs));
+                }
+            }
+        }
+        results.pop().unwrap()
+    }
+
+    /// Build the expression for a term in the arena.
+    ///
+    /// Functions are given numerical parameter names in the same way as `normalize_variables`,
+    /// so interning an expression and converting it back is the same as normalizing it. A bound
+    /// variable that isn't bound by any function in the term is named by its index, as `#0`.
+    pub fn to_expression(&self, id: TermId) -> Expression {
+        enum Task {
+            Visit(TermId),
+            Function(Symbol),
+            Application,
+        }
+
+        let free = self.free_labels(id);
+        let mut next_name = (1..)
+            .map(Symbol::number)
+            .filter(|name| !free.contains(name));
+        let mut scope: Vec<Symbol> = Vec::new();
+        let mut tasks = vec![Task::Visit(id)];
+        let mut results = Vec::new();
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit(id) => match self.get(id) {
+                    Term::Bound(index) => match scope.iter().rev().nth(*index as usize) {
+                        Some(param) => results.push(Expression::Variable(*param)),
+                        None => results.push(Expression::new_variable(format!("#{}", index))),
+                    },
+                    Term::Free(label) => results.push(Expression::Variable(*label)),
+                    Term::Function(body) => {
+                        let param = next_name.next().unwrap();
+                        scope.push(param);
+                        tasks.push(Task::Function(param));
+                        tasks.push(Task::Visit(*body));
+                    }
+                    Term::Application(lhs, rhs) => {
+                        tasks.push(Task::Application);
+                        tasks.push(Task::Visit(*rhs));
+                        tasks.push(Task::Visit(*lhs));
+                    }
+                },
+                Task::Function(param) => {
+                    scope.pop();
+                    let body = results.pop().unwrap();
+                    results.push(Expression::new_function(param, body));
+                }
+                Task::Application => {
+                    let rhs = results.pop().unwrap();
+                    let lhs = results.pop().unwrap();
+                    results.push(Expression::new_application(lhs, rhs));
+                }
+            }
+        }
+        results.pop().unwrap()
+    }
+
+    /// The labels of the free variables in a term.
+    fn free_labels(&self, id: TermId) -> HashSet<Symbol> {
+        let mut labels = HashSet::new();
+        let mut visited = HashSet::new();
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            match self.get(id) {
+                Term::Bound(_) => {}
+                Term::Free(label) => {
+                    labels.insert(*label);
+                }
+                Term::Function(body) => stack.push(*body),
+                Term::Application(lhs, rhs) => stack.extend([*lhs, *rhs]),
+            }
+        }
+        labels
+    }
+
+    /// Evaluate a term to normal form, and return the handle to the result.
+    ///
+    /// Reduction is in normal order, so the normal form is found whenever one exists. Normal
+    /// forms are remembered for every subterm evaluated, so shared subterms are only evaluated
+    /// once.
+    pub fn evaluate(&mut self, id: TermId) -> TermId {
+        enum Task {
+            Visit(TermId),
+            Function(TermId),
+            Application(TermId, usize),
+        }
+
+        let mut tasks = vec![Task::Visit(id)];
+        let mut results = Vec::new();
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit(id) => {
+                    if let Some(normal) = self.normal_forms.get(&id) {
+                        results.push(*normal);
+                        continue;
+                    }
+                    let (head, args) = self.whnf_spine(id);
+                    match (self.get(head).clone(), args.is_empty()) {
+                        (Term::Function(body), true) => {
+                            tasks.push(Task::Function(id));
+                            tasks.push(Task::Visit(body));
+                        }
+                        _ => {
+                            // The head is a variable, so only the arguments can be reduced.
+                            results.push(head);
+                            tasks.push(Task::Application(id, args.len()));
+                            tasks.extend(args.into_iter().map(Task::Visit));
+                        }
+                    }
+                }
+                Task::Function(id) => {
+                    let body = results.pop().unwrap();
+                    let normal = self.function(body);
+                    self.normal_forms.insert(id, normal);
+                    results.push(normal);
+                }
+                Task::Application(id, count) => {
+                    let args = results.split_off(results.len() - count);
+                    let mut normal = results.pop().unwrap();
+                    for arg in args.into_iter().rev() {
+                        normal = self.application(normal, arg);
+                    }
+                    self.normal_forms.insert(id, normal);
+                    results.push(normal);
+                }
+            }
+        }
+        results.pop().unwrap()
+    }
+
+    /// Reduce a term to weak head normal form.
+    ///
+    /// Returns the head of the result, and the arguments it is applied to, with the first
+    /// argument first: `(f a) b` gives `f` and `[a, b]`.
+    fn whnf_spine(&mut self, id: TermId) -> (TermId, Vec<TermId>) {
+        let mut head = id;
+        let mut args = Vec::new();
+        loop {
+            match self.get(head).clone() {
+                Term::Application(lhs, rhs) => {
+                    args.push(rhs);
+                    head = lhs;
+                }
+                Term::Function(body) if !args.is_empty() => {
+                    let arg = args.pop().unwrap();
+                    head = self.instantiate(body, arg);
+                }
+                _ => {
+                    args.reverse();
+                    return (head, args);
+                }
+            }
+        }
+    }
+
+    /// Substitute `arg` for the variable bound by the function with body `body`.
+    fn instantiate(&mut self, body: TermId, arg: TermId) -> TermId {
+        self.rewrite_bound(body, &mut |arena, index, depth| {
+            if index == depth {
+                arena.shift(arg, depth)
+            } else if index > depth {
+                arena.bound(index - 1)
+            } else {
+                arena.bound(index)
+            }
+        })
+    }
+
+    /// Increase the free de Bruijn indices of a term by `amount`.
+    fn shift(&mut self, id: TermId, amount: u32) -> TermId {
+        if amount == 0 {
+            return id;
+        }
+        self.rewrite_bound(id, &mut |arena, index, depth| {
+            if index >= depth {
+                arena.bound(index + amount)
+            } else {
+                arena.bound(index)
+            }
+        })
+    }
+
+    /// Rebuild a term, replacing each variable with de Bruijn index `index` found under
+    /// `depth` functions with `replace(arena, index, depth)`.
+    ///
+    /// Subterms with no free indices are left alone, and shared subterms are only rewritten once.
+    fn rewrite_bound(
+        &mut self,
+        id: TermId,
+        replace: &mut dyn FnMut(&mut Self, u32, u32) -> TermId,
+    ) -> TermId {
+        enum Task {
+            Visit(TermId, u32),
+            Function(TermId, u32),
+            Application(TermId, u32),
+        }
+
+        let mut done: HashMap<(TermId, u32), TermId> = HashMap::new();
+        let mut tasks = vec![Task::Visit(id, 0)];
+        let mut results = Vec::new();
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit(id, depth) => {
+                    if self.loose(id) <= depth {
+                        results.push(id);
+                    } else if let Some(result) = done.get(&(id, depth)) {
+                        results.push(*result);
+                    } else {
+                        match self.get(id).clone() {
+                            Term::Bound(index) => {
+                                let result = replace(self, index, depth);
+                                done.insert((id, depth), result);
+                                results.push(result);
+                            }
+                            Term::Free(_) => results.push(id),
+                            Term::Function(body) => {
+                                tasks.push(Task::Function(id, depth));
+                                tasks.push(Task::Visit(body, depth + 1));
+                            }
+                            Term::Application(lhs, rhs) => {
+                                tasks.push(Task::Application(id, depth));
+                                tasks.push(Task::Visit(rhs, depth));
+                                tasks.push(Task::Visit(lhs, depth));
+                            }
+                        }
+                    }
+                }
+                Task::Function(id, depth) => {
+                    let body = results.pop().unwrap();
+                    let result = self.function(body);
+                    done.insert((id, depth), result);
+                    results.push(result);
+                }
+                Task::Application(id, depth) => {
+                    let rhs = results.pop().unwrap();
+                    let lhs = results.pop().unwrap();
+                    let result = self.application(lhs, rhs);
+                    done.insert((id, depth), result);
+                    results.push(result);
+                }
+            }
+        }
+        results.pop().unwrap()
+    }
+}
@@ -1,14 +1,372 @@
-#![allow(dead_code)]
-#![allow(unused_variables)]
-
-fn main() {
-    let args: Vec<String> = std::env::args().skip(1).collect();
-    let code = args.join(" ");
-    let parsed: lambda::ParseResult = code.parse();
-
-    println!(
-        "'{}' -> {}",
-        code,
-        parsed.map_or_else(|e| format!("{}", e), |p| format!("{}", p.evaluate()))
-    );
+use std::io::{IsTerminal, Read};
+use std::process::ExitCode;
+use std::{env, fs, io};
+
+use lambda::{Expression, Highlight, LambdaError};
+
+const USAGE: &str = "\
+Usage: evaluate [OPTIONS] [EXPRESSION...]
+
+Evaluate a lambda expression and print the result. The expression is read from the arguments,
+from a file, or from stdin if neither is given.
+
+Options:
+  -f, --file PATH          Read the expression from PATH, or from stdin if PATH is -
+  --strategy NAME          eager (default), normal, lazy, nbe or optimal
+  --max-steps N            Fail after N β-reductions (eager and normal strategies). A single
+                           reduction can copy a large term, so this doesn't bound the time
+  --trace                  Print every step of a normal order reduction
+  --highlight MODE         Mark the redexes of a text trace: auto (default), color, markers
+                           or none. auto colors them in a terminal, and uses markers otherwise
+  --output-format FORMAT   text (default), json, dot or latex
+  --no-normalize           Leave the functions of the result named as the evaluator left them
+  --numbered               Name the functions of the result 1, 2, ... in order
+  --stats                  Print statistics about the input, result and evaluation
+  -h, --help               Print this help
+
+Exit codes: 0 on success, 1 on a syntax error, 2 on invalid usage, 3 on a runtime error.";
+
+const SYNTAX_ERROR: u8 = 1;
+const USAGE_ERROR: u8 = 2;
+const RUNTIME_ERROR: u8 = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Strategy {
+    Eager,
+    Normal,
+    Lazy,
+    Nbe,
+    Optimal,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+    Dot,
+    Latex,
+}
+
+/// How to name the functions of the result.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Names {
+    /// After the parameters of the input where possible, see `evaluate_readable`.
+    Readable,
+    /// As `normalize_variables` does.
+    Numbered,
+    /// As the evaluator left them.
+    Unchanged,
+}
+
+enum Input {
+    Code(String),
+    File(String),
+    Stdin,
+}
+
+struct Options {
+    input: Input,
+    strategy: Option<Strategy>,
+    max_steps: Option<usize>,
+    trace: bool,
+    /// How to mark redexes in a trace, or `None` to decide based on the terminal.
+    highlight: Option<Highlight>,
+    format: Format,
+    names: Names,
+    stats: bool,
+}
+
+/// A reason to stop, with the exit code to stop with.
+struct Failure {
+    code: u8,
+    message: String,
+}
+
+impl Failure {
+    fn usage(message: impl Into<String>) -> Self {
+        Self {
+            code: USAGE_ERROR,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<LambdaError> for Failure {
+    fn from(error: LambdaError) -> Self {
+        let code = match error {
+            LambdaError::SyntaxError(_) => SYNTAX_ERROR,
+            LambdaError::RuntimeError(_) => RUNTIME_ERROR,
+        };
+        Self {
+            code,
+            message: error.to_string(),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    match parse_args(args).and_then(run) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => {
+            eprintln!("{}", failure.message);
+            if failure.code == USAGE_ERROR {
+                eprintln!("Run with --help for usage.");
+            }
+            ExitCode::from(failure.code)
+        }
+    }
+}
+
+fn parse_args(args: Vec<String>) -> Result<Options, Failure> {
+    let mut options = Options {
+        input: Input::Stdin,
+        strategy: None,
+        max_steps: None,
+        trace: false,
+        highlight: None,
+        format: Format::Text,
+        names: Names::Readable,
+        stats: false,
+    };
+    let mut words = Vec::new();
+    let mut file = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        // Options with a value can be written as `--name value` or `--name=value`.
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || match inline_value {
+            Some(value) => Ok(value.to_string()),
+            None => args
+                .next()
+                .ok_or_else(|| Failure::usage(format!("Missing value for {}.", name))),
+        };
+        match name.as_str() {
+            "-f" | "--file" => file = Some(value()?),
+            "--strategy" => {
+                options.strategy = Some(match value()?.as_str() {
+                    "eager" => Strategy::Eager,
+                    "normal" => Strategy::Normal,
+                    "lazy" => Strategy::Lazy,
+                    "nbe" => Strategy::Nbe,
+                    "optimal" => Strategy::Optimal,
+                    other => return Err(Failure::usage(format!("Unknown strategy {}.", other))),
+                })
+            }
+            "--max-steps" => {
+                let steps = value()?;
+                let steps = steps
+                    .parse()
+                    .map_err(|_| Failure::usage(format!("Invalid number of steps {}.", steps)))?;
+                options.max_steps = Some(steps);
+            }
+            "--trace" => options.trace = true,
+            "--highlight" => {
+                options.highlight = match value()?.as_str() {
+                    "auto" => None,
+                    "color" => Some(Highlight::Color),
+                    "markers" => Some(Highlight::Markers),
+                    "none" => Some(Highlight::None),
+                    other => return Err(Failure::usage(format!("Unknown highlight {}.", other))),
+                }
+            }
+            "--output-format" => {
+                options.format = match value()?.as_str() {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    "dot" => Format::Dot,
+                    "latex" => Format::Latex,
+                    other => {
+                        return Err(Failure::usage(format!("Unknown output format {}.", other)))
+                    }
+                }
+            }
+            "--no-normalize" => options.names = Names::Unchanged,
+            "--numbered" => options.names = Names::Numbered,
+            "--stats" => options.stats = true,
+            _ if arg.starts_with("--") => {
+                return Err(Failure::usage(format!("Unknown option {}.", arg)))
+            }
+            _ => words.push(arg),
+        }
+    }
+
+    options.input = match (file, words.is_empty()) {
+        (Some(_), false) => {
+            return Err(Failure::usage(
+                "Pass either an expression or a file, not both.",
+            ))
+        }
+        (Some(path), true) if path == "-" => Input::Stdin,
+        (Some(path), true) => Input::File(path),
+        (None, false) => Input::Code(words.join(" ")),
+        (None, true) => Input::Stdin,
+    };
+
+    let strategy = options.strategy.unwrap_or(if options.trace {
+        Strategy::Normal
+    } else {
+        Strategy::Eager
+    });
+    if options.trace && strategy != Strategy::Normal {
+        return Err(Failure::usage("--trace needs the normal strategy."));
+    }
+    if options.max_steps.is_some() && !matches!(strategy, Strategy::Eager | Strategy::Normal) {
+        return Err(Failure::usage(
+            "--max-steps needs the eager or normal strategy.",
+        ));
+    }
+    if options.stats && (strategy != Strategy::Eager || options.max_steps.is_some()) {
+        return Err(Failure::usage(
+            "--stats needs the eager strategy, without --max-steps.",
+        ));
+    }
+    if options.trace && options.format == Format::Dot {
+        return Err(Failure::usage("--trace can't be drawn as dot."));
+    }
+    if options.format == Format::Json && !cfg!(feature = "json") {
+        return Err(Failure::usage(
+            "JSON output needs the evaluate binary to be built with the json feature.",
+        ));
+    }
+    options.strategy = Some(strategy);
+    Ok(options)
+}
+
+fn read_input(input: &Input) -> Result<String, Failure> {
+    let io_failure = |error: io::Error| Failure::usage(format!("Can't read the input: {}", error));
+    match input {
+        Input::Code(code) => Ok(code.clone()),
+        Input::File(path) => fs::read_to_string(path).map_err(io_failure),
+        Input::Stdin => {
+            let mut code = String::new();
+            io::stdin().read_to_string(&mut code).map_err(io_failure)?;
+            Ok(code)
+        }
+    }
+}
+
+fn run(options: Options) -> Result<(), Failure> {
+    let code = read_input(&options.input)?;
+    let expr: Expression = code.parse()?;
+
+    if options.trace {
+        let trace = expr.trace(options.max_steps.unwrap_or(usize::MAX));
+        let highlight = options.highlight.unwrap_or_else(|| {
+            let color = io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none();
+            if color {
+                Highlight::Color
+            } else {
+                Highlight::Markers
+            }
+        });
+        print_trace(&trace, options.format, highlight);
+        if !trace.is_complete() {
+            return Err(Failure::from(LambdaError::RuntimeError(format!(
+                "Reduction stopped after the maximum of {} β-reductions.",
+                trace.steps()
+            ))));
+        }
+        return Ok(());
+    }
+
+    if options.stats {
+        let before = expr.metrics();
+        let (result, evaluation) = match options.names {
+            Names::Readable => lambda::evaluate_readable_with_stats(expr),
+            names => {
+                let (result, evaluation) = expr.evaluate_with_stats();
+                (name(result, names), evaluation)
+            }
+        };
+        print_result(&result, options.format);
+        let stats = format!(
+            "input:  {}\nresult: {}\nevaluation: {}",
+            before,
+            result.metrics(),
+            evaluation
+        );
+        // Keep other formats machine readable.
+        match options.format {
+            Format::Text => println!("{}", stats),
+            _ => eprintln!("{}", stats),
+        }
+        return Ok(());
+    }
+
+    let result = match (options.strategy.unwrap(), options.max_steps) {
+        (Strategy::Eager, None) => match options.names {
+            Names::Readable => expr.evaluate_readable(),
+            Names::Numbered => expr.evaluate(),
+            Names::Unchanged => {
+                lambda::evaluate_no_normalization(lambda::normalize_variables(expr))
+            }
+        },
+        (Strategy::Eager, Some(max_steps)) => match options.names {
+            Names::Readable => lambda::evaluate_readable_limited(expr, max_steps)?,
+            names => name(lambda::evaluate_limited(expr, max_steps)?, names),
+        },
+        (Strategy::Normal, max_steps) => name(
+            lambda::reduce_normal_order(expr, max_steps.unwrap_or(usize::MAX))?,
+            options.names,
+        ),
+        (Strategy::Lazy, _) => match options.names {
+            Names::Readable => lambda::evaluate_lazy_readable(expr),
+            names => name(expr.evaluate_lazy(), names),
+        },
+        (Strategy::Nbe, _) => match options.names {
+            Names::Readable => lambda::evaluate_nbe_readable(expr),
+            names => name(expr.evaluate_nbe(), names),
+        },
+        (Strategy::Optimal, _) => match options.names {
+            Names::Readable => lambda::evaluate_optimal_readable(expr)?,
+            names => name(lambda::evaluate_optimal(expr)?, names),
+        },
+    };
+    print_result(&result, options.format);
+    Ok(())
+}
+
+/// Rename the functions of a result that is normalized, or of one reduced in normal order.
+fn name(result: Expression, names: Names) -> Expression {
+    match names {
+        Names::Readable => lambda::readable_names(result),
+        Names::Numbered => lambda::normalize_variables(result),
+        Names::Unchanged => result,
+    }
+}
+
+fn print_result(result: &Expression, format: Format) {
+    match format {
+        Format::Text => println!("{}", result),
+        Format::Json => println!("{}", to_json(result)),
+        Format::Dot => print!("{}", result.to_dot()),
+        Format::Latex => println!("{}", result.to_latex()),
+    }
+}
+
+fn print_trace(trace: &lambda::Trace<lambda::Symbol>, format: Format, highlight: Highlight) {
+    match format {
+        Format::Text => print!("{}", trace.to_text(highlight)),
+        Format::Json => println!("{}", to_json(trace.terms())),
+        Format::Dot => unreachable!("rejected when parsing the arguments"),
+        Format::Latex => print!("{}", trace.to_latex()),
+    }
+}
+
+#[cfg(feature = "json")]
+fn to_json<T: serde::Serialize + ?Sized>(value: &T) -> String {
+    serde_json::to_string(value).expect("expressions can be written as JSON")
+}
+
+#[cfg(not(feature = "json"))]
+fn to_json<T: ?Sized>(_value: &T) -> String {
+    unreachable!("rejected when parsing the arguments")
 }
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+use crate::expr::Expression;
+use crate::label::Label;
+
+/// Options for drawing an expression with `Expression::to_dot_with`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DotOptions {
+    /// Draw a dashed edge from each bound variable back to the function that binds it, labelled
+    /// with its de Bruijn index: 1 for the closest enclosing function, 2 for the one around it,
+    /// and so on.
+    pub binding_edges: bool,
+}
+
+impl<V: Label + fmt::Display> Expression<V> {
+    /// Render the syntax tree of the expression in the Graphviz DOT language.
+    ///
+    /// Functions are drawn as `λx`, applications as `@` with the lhs on the left, and variables
+    /// by their name.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::Expression;
+    ///
+    /// let expr: Expression = "λx.x y".parse().unwrap();
+    /// assert_eq!(
+    ///     expr.to_dot(),
+    ///     r#"digraph {
+    ///     node [shape=none];
+    ///     n0 [label="λx"];
+    ///     n1 [label="@"];
+    ///     n0 -> n1;
+    ///     n2 [label="x"];
+    ///     n1 -> n2;
+    ///     n3 [label="y"];
+    ///     n1 -> n3;
+    /// }
+    /// "#
+    /// );
+    /// ```
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&DotOptions::default())
+    }
+
+    /// Render the syntax tree of the expression in the Graphviz DOT language, with the given
+    /// options.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::{DotOptions, Expression};
+    ///
+    /// let expr: Expression = "λx.λy.x".parse().unwrap();
+    /// let options = DotOptions { binding_edges: true };
+    /// assert!(expr
+    ///     .to_dot_with(&options)
+    ///     .contains("n2 -> n0 [style=dashed, constraint=false, label=\"2\"];"));
+    /// ```
+    pub fn to_dot_with(&self, options: &DotOptions) -> String {
+        enum Task<'a, V: Label> {
+            Visit(&'a Expression<V>, Option<usize>),
+            ExitFunction(&'a V, Option<usize>),
+        }
+
+        let mut dot = String::from("digraph {\n    node [shape=none];\n");
+        // The functions in scope, innermost last, and the scope of each parameter.
+        let mut binders: Vec<usize> = Vec::new();
+        let mut scopes: HashMap<&V, usize> = HashMap::new();
+        let mut next_id = 0;
+        let mut tasks = vec![Task::Visit(self, None)];
+        while let Some(task) = tasks.pop() {
+            let (expr, parent) = match task {
+                Task::Visit(expr, parent) => (expr, parent),
+                Task::ExitFunction(param, prev) => {
+                    binders.pop();
+                    match prev {
+                        Some(prev) => scopes.insert(param, prev),
+                        None => scopes.remove(param),
+                    };
+                    continue;
+                }
+            };
+            let id = next_id;
+            next_id += 1;
+            let label = match expr {
+                Expression::Variable(label) => label.to_string(),
+                Expression::Function(param, _) => format!("λ{}", param),
+                Expression::Application(_, _) => "@".to_string(),
+            };
+            writeln!(dot, "    n{} [label=\"{}\"];", id, escape(&label)).unwrap();
+            if let Some(parent) = parent {
+                writeln!(dot, "    n{} -> n{};", parent, id).unwrap();
+            }
+            match expr {
+                Expression::Variable(label) => {
+                    if let Some(&scope) = scopes.get(label).filter(|_| options.binding_edges) {
+                        let index = binders.len() - scope;
+                        writeln!(
+                            dot,
+                            "    n{} -> n{} [style=dashed, constraint=false, label=\"{}\"];",
+                            id, binders[scope], index
+                        )
+                        .unwrap();
+                    }
+                }
+                Expression::Function(param, body) => {
+                    let prev = scopes.insert(param, binders.len());
+                    binders.push(id);
+                    tasks.push(Task::ExitFunction(param, prev));
+                    tasks.push(Task::Visit(body, Some(id)));
+                }
+                Expression::Application(lhs, rhs) => {
+                    tasks.push(Task::Visit(rhs, Some(id)));
+                    tasks.push(Task::Visit(lhs, Some(id)));
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Escape a label for use in a quoted DOT string.
+pub(crate) fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -1,7 +1,14 @@
 use std::fmt;
 
 /// An error with a lambda expression.
+///
+/// With the `serde` feature, errors serialize as `{"type": "SyntaxError", "message": "..."}`.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", content = "message")
+)]
 pub enum LambdaError {
     SyntaxError(String),
     RuntimeError(String),
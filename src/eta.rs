@@ -0,0 +1,149 @@
+use crate::evaluate::evaluate;
+use crate::expr::{Expression, Parts};
+use crate::normalize::normalize_variables;
+use crate::symbol::Symbol;
+
+/// Perform all η-reductions in an expression.
+///
+/// An η-reduction replaces a function `λx.M x` with just `M`, as long as `x` is not free in `M`.
+/// The result contains no such functions.
+///
+/// # Examples
+/// ```rust
+/// use lambda::eta_reduce;
+///
+/// let expr = "λx.λy.f x y".parse().unwrap();
+/// assert_eq!(eta_reduce(expr).to_string(), "f");
+///
+/// let expr = "λx.x x".parse().unwrap();
+/// assert_eq!(eta_reduce(expr).to_string(), "λx.x x");
+/// ```
+pub fn eta_reduce(expr: Expression) -> Expression {
+    enum Task {
+        Visit(Expression),
+        Function(Symbol),
+        Application,
+    }
+
+    let mut tasks = vec![Task::Visit(expr)];
+    let mut results = Vec::new();
+    while let Some(task) = tasks.pop() {
+        match task {
+            Task::Visit(expr) => match expr.into_parts() {
+                Parts::Variable(label) => results.push(Expression::Variable(label)),
+                Parts::Function(param, body) => {
+                    tasks.push(Task::Function(param));
+                    tasks.push(Task::Visit(body));
+                }
+                Parts::Application(lhs, rhs) => {
+                    tasks.push(Task::Application);
+                    tasks.push(Task::Visit(rhs));
+                    tasks.push(Task::Visit(lhs));
+                }
+            },
+            Task::Function(param) => {
+                // The body is already reduced, so if the function is an η-redex, the result of
+                // contracting it needs no more work.
+                let body = results.pop().unwrap();
+                let is_redex = match &body {
+                    Expression::Application(lhs, rhs) => {
+                        matches!(rhs.as_ref(), Expression::Variable(arg) if *arg == param)
+                            && !is_free_in(param, lhs)
+                    }
+                    _ => false,
+                };
+                if !is_redex {
+                    results.push(Expression::new_function(param, body));
+                } else if let Parts::Application(lhs, _) = body.into_parts() {
+                    results.push(lhs);
+                }
+            }
+            Task::Application => {
+                let rhs = results.pop().unwrap();
+                let lhs = results.pop().unwrap();
+                results.push(Expression::new_application(lhs, rhs));
+            }
+        }
+    }
+    results.pop().unwrap()
+}
+
+/// Perform an η-expansion, turning `M` into `λx.M x`.
+///
+/// The parameter `x` is chosen so that it is not free in `M`. It will be `x`, with as many
+/// primes appended as is necessary to make it unique.
+///
+/// # Examples
+/// ```rust
+/// use lambda::eta_expand;
+///
+/// assert_eq!(eta_expand("f".parse().unwrap()).to_string(), "λx.f x");
+/// assert_eq!(eta_expand("f x".parse().unwrap()).to_string(), "λx'.(f x) x'");
+/// ```
+pub fn eta_expand(expr: Expression) -> Expression {
+    let mut param = String::from("x");
+    while is_free_in(Symbol::new(&param), &expr) {
+        param.push('\'');
+    }
+    let arg = Expression::new_variable(&param);
+    Expression::new_function(param, Expression::new_application(expr, arg))
+}
+
+/// Evaluate an expression to its βη-normal form, and return the normalized result.
+///
+/// This is the result of `evaluate` with all η-reductions performed. Terms that are equal
+/// under η-conversion, such as `λx.λy.x y` and `λx.x`, have the same βη-normal form.
+///
+/// # Examples
+/// ```rust
+/// use lambda::evaluate_beta_eta;
+///
+/// let expr = "(λx.λy.x y) (λz.z)".parse().unwrap();
+/// assert_eq!(evaluate_beta_eta(expr).to_string(), "λ1.1");
+/// ```
+pub fn evaluate_beta_eta(expr: Expression) -> Expression {
+    // A β-normal form can't have β-redexes introduced by η-reduction, so the result is in
+    // βη-normal form.
+    normalize_variables(eta_reduce(evaluate(expr)))
+}
+
+/// Check if two expressions are equal under βη-conversion.
+///
+/// This evaluates both expressions, so it will not terminate if either of them has no normal
+/// form.
+///
+/// # Examples
+/// ```rust
+/// use lambda::beta_eta_equivalent;
+///
+/// let lhs = "λx.λy.x y".parse().unwrap();
+/// let rhs = "λx.x".parse().unwrap();
+/// assert!(beta_eta_equivalent(lhs, rhs));
+/// ```
+pub fn beta_eta_equivalent(lhs: Expression, rhs: Expression) -> bool {
+    evaluate_beta_eta(lhs) == evaluate_beta_eta(rhs)
+}
+
+/// Check if the variable `name` occurs free in `expr`.
+fn is_free_in(name: Symbol, expr: &Expression) -> bool {
+    let mut stack = vec![expr];
+    while let Some(expr) = stack.pop() {
+        match expr {
+            Expression::Variable(label) => {
+                if *label == name {
+                    return true;
+                }
+            }
+            Expression::Function(param, body) => {
+                if *param != name {
+                    stack.push(body);
+                }
+            }
+            Expression::Application(lhs, rhs) => {
+                stack.push(rhs);
+                stack.push(lhs);
+            }
+        }
+    }
+    false
+}
@@ -1,7 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::time::{Duration, Instant};
 
-use crate::expr::Expression;
-use crate::normalize::normalize_variables;
+use crate::cancel::CancellationToken;
+use crate::error::LambdaError;
+use crate::expr::{Expression, Parts};
+use crate::label::Label;
+use crate::normalize::{
+    alpha_equivalent, normalize_variables, rename_functions, try_normalize_variables, unused_names,
+};
 
 /// Evaluate an expression and return the normalized result.
 ///
@@ -18,7 +25,7 @@ use crate::normalize::normalize_variables;
 /// let three = church::add() * church::one() * church::two();
 /// assert_eq!(three.evaluate(), church::three().normalize());
 /// ```
-pub fn evaluate(expr: Expression) -> Expression {
+pub fn evaluate<V: Label>(expr: Expression<V>) -> Expression<V> {
     evaluate_normalized(normalize_variables(expr))
 }
 
@@ -30,7 +37,7 @@ pub fn evaluate(expr: Expression) -> Expression {
 /// shadowing in the expression.
 ///
 /// The result of the evaluation will be normalized.
-pub fn evaluate_normalized(expr: Expression) -> Expression {
+pub fn evaluate_normalized<V: Label>(expr: Expression<V>) -> Expression<V> {
     normalize_variables(evaluate_no_normalization(expr))
 }
 
@@ -38,52 +45,566 @@ pub fn evaluate_normalized(expr: Expression) -> Expression {
 ///
 /// See evaluate_normalized for more information. This will do what evaluate_normalized does,
 /// only it will not apply normalization to the result of the evaluation.
-pub fn evaluate_no_normalization(expr: Expression) -> Expression {
-    _evaluate(expr, &mut HashMap::new())
+///
+/// # Panics
+/// If there aren't enough numbered labels of type `V` to give new names to the functions in
+/// every copy of a value, as the evaluation does so that no function shadows another.
+pub fn evaluate_no_normalization<V: Label>(expr: Expression<V>) -> Expression<V> {
+    match _evaluate(expr, &mut HashMap::new(), &mut ()) {
+        Ok(value) => value,
+        // Without checks, only running out of labels to rename functions with can fail.
+        Err(error) => panic!("{}", error),
+    }
+}
+
+/// Evaluate an expression like `evaluate_no_normalization`, and also return the function of
+/// `expr` that each function of the result renamed by the evaluation was copied from.
+pub(crate) fn evaluate_with_origins<V: Label>(
+    expr: Expression<V>,
+) -> (Expression<V>, HashMap<V, V>) {
+    match evaluate_observed(expr, &mut ()) {
+        Ok(result) => result,
+        Err(error) => panic!("{}", error),
+    }
+}
+
+/// Statistics about the work done to evaluate an expression.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Stats {
+    /// The number of β-reductions performed.
+    pub beta_steps: usize,
+    /// The size of the largest term built: the input, an evaluated argument, the result of a
+    /// β-reduction, or the final result.
+    pub max_size: usize,
+    /// The number of variables replaced by the value bound to them.
+    pub substitutions: usize,
+    /// The number of variables looked up in the bindings, whether they were bound or not.
+    pub lookups: usize,
+    /// The wall time spent evaluating, including normalization.
+    pub time: Duration,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} β-steps, max size {}, {} substitutions, {} lookups, {:?}",
+            self.beta_steps, self.max_size, self.substitutions, self.lookups, self.time
+        )
+    }
+}
+
+impl Stats {
+    fn record_size<V: Label>(&mut self, expr: &Expression<V>) {
+        self.max_size = self.max_size.max(expr.size());
+    }
+}
+
+impl<V: Label> Observer<V> for Stats {
+    fn lookup(&mut self, bound: bool) {
+        self.lookups += 1;
+        self.substitutions += usize::from(bound);
+    }
+
+    fn beta(&mut self, arg: &Expression<V>) -> Result<(), LambdaError> {
+        self.beta_steps += 1;
+        self.record_size(arg);
+        Ok(())
+    }
+
+    fn reduced(&mut self, value: &Expression<V>, _depth: usize) -> Result<(), LambdaError> {
+        self.record_size(value);
+        Ok(())
+    }
+}
+
+/// Evaluate an expression like `evaluate`, and also return statistics about the evaluation.
+///
+/// Measuring the sizes of intermediate terms takes time of its own, so this is slower than
+/// `evaluate`.
+///
+/// # Examples
+/// ```rust
+/// use lambda::church;
+///
+/// let (two, stats) = lambda::evaluate_with_stats(church::succ() * church::one());
+/// assert_eq!(two, church::two().normalize());
+/// assert_eq!(stats.beta_steps, 3);
+/// assert_eq!(stats.max_size, 16);
+/// ```
+pub fn evaluate_with_stats<V: Label>(expr: Expression<V>) -> (Expression<V>, Stats) {
+    evaluate_with_stats_then(expr, |value, _| normalize_variables(value))
+}
+
+/// Evaluate an expression like `evaluate_with_stats`, but pass the result of the evaluation to
+/// `finish` rather than normalizing it, along with the origins of its functions like
+/// `evaluate_with_origins`.
+pub(crate) fn evaluate_with_stats_then<V: Label>(
+    expr: Expression<V>,
+    finish: impl FnOnce(Expression<V>, HashMap<V, V>) -> Expression<V>,
+) -> (Expression<V>, Stats) {
+    let start = Instant::now();
+    let mut stats = Stats::default();
+    stats.record_size(&expr);
+    let result = match evaluate_observed(normalize_variables(expr), &mut stats) {
+        Ok((value, origins)) => finish(value, origins),
+        Err(error) => panic!("{}", error),
+    };
+    stats.record_size(&result);
+    stats.time = start.elapsed();
+    (result, stats)
+}
+
+/// Evaluate an expression like `evaluate`, but fail instead of looping forever on terms that
+/// trivially reduce back to themselves.
+///
+/// A loop is reported when the result of a β-reduction is α-equivalent to one of the last few
+/// results reduced in the same place, such as for `Ω = (λx.x x) (λx.x x)`, which reduces to
+/// itself in one step. Terms that loop without repeating, or that grow as they reduce, are not
+/// detected, and still evaluate forever.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{church, evaluate_checked, Expression};
+///
+/// let omega: Expression = "(λx.x x) (λx.x x)".parse().unwrap();
+/// assert!(evaluate_checked(omega).is_err());
+///
+/// let two = church::succ() * church::one();
+/// assert_eq!(evaluate_checked(two).unwrap(), church::two().normalize());
+/// ```
+pub fn evaluate_checked<V: Label + fmt::Display>(
+    expr: Expression<V>,
+) -> Result<Expression<V>, LambdaError> {
+    let expr = try_normalize_variables(expr)?;
+    let value = _evaluate(expr, &mut HashMap::new(), &mut LoopDetector::default())?;
+    try_normalize_variables(value)
+}
+
+/// Evaluate an expression like `evaluate`, but stop with an error if `token` is cancelled.
+///
+/// The token is checked every few steps of the evaluation, whether they β-reduce anything or
+/// just copy the values of variables, so a host can stop a runaway evaluation from another
+/// thread by cancelling it, or by giving the token a deadline. Normalizing the input and the
+/// result, and copying a single value, can't be interrupted.
+///
+/// # Examples
+/// ```rust
+/// use std::time::Duration;
+/// use lambda::{evaluate_cancellable, CancellationToken, Expression};
+///
+/// let omega: Expression = "(λx.x x) (λx.x x)".parse().unwrap();
+/// let token = CancellationToken::with_timeout(Duration::from_millis(10));
+/// assert!(evaluate_cancellable(omega, &token).is_err());
+/// ```
+pub fn evaluate_cancellable<V: Label>(
+    expr: Expression<V>,
+    token: &CancellationToken,
+) -> Result<Expression<V>, LambdaError> {
+    let mut cancellation = Cancellation {
+        token,
+        steps: 0,
+        beta_steps: 0,
+    };
+    let expr = try_normalize_variables(expr)?;
+    let value = _evaluate(expr, &mut HashMap::new(), &mut cancellation)?;
+    try_normalize_variables(value)
 }
 
-fn _evaluate(expr: Expression, bindings: &mut HashMap<String, Expression>) -> Expression {
-    match expr {
-        Expression::Variable(label) => {
-            // If the variable is bound, return the bound value, otherwise just return the variable.
-            (*bindings.get(&label).unwrap_or(&Expression::Variable(label))).clone()
+/// Evaluate an expression like `evaluate`, but stop with an error after `max_steps`
+/// β-reductions.
+///
+/// The evaluation always finishes, but the limit doesn't bound how long it takes: a single
+/// β-reduction can copy a value of any size, and values can grow exponentially with the number
+/// of reductions, as for `two two two two two` with Church numerals. Use `evaluate_cancellable`
+/// with a deadline to bound the time instead.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{church, evaluate_limited, Expression};
+///
+/// let omega: Expression = "(λx.x x) (λx.x x)".parse().unwrap();
+/// assert!(evaluate_limited(omega, 1000).is_err());
+///
+/// let two = church::succ() * church::one();
+/// assert_eq!(evaluate_limited(two.clone(), 3).unwrap(), church::two().normalize());
+/// assert!(evaluate_limited(two, 2).is_err());
+/// ```
+pub fn evaluate_limited<V: Label>(
+    expr: Expression<V>,
+    max_steps: usize,
+) -> Result<Expression<V>, LambdaError> {
+    let expr = try_normalize_variables(expr)?;
+    let (value, _) = evaluate_limited_with_origins(expr, max_steps)?;
+    try_normalize_variables(value)
+}
+
+/// Evaluate an expression that is already normalized like `evaluate_limited`, without
+/// post-normalization, and also return the origins of the functions of the result like
+/// `evaluate_with_origins`.
+pub(crate) fn evaluate_limited_with_origins<V: Label>(
+    expr: Expression<V>,
+    max_steps: usize,
+) -> Result<(Expression<V>, HashMap<V, V>), LambdaError> {
+    let mut limit = StepLimit {
+        max_steps,
+        steps: 0,
+    };
+    evaluate_observed(expr, &mut limit)
+}
+
+/// Evaluate an expression with `observer`, keeping track of where renamed functions came from.
+fn evaluate_observed<V: Label>(
+    expr: Expression<V>,
+    observer: &mut impl Observer<V>,
+) -> Result<(Expression<V>, HashMap<V, V>), LambdaError> {
+    let mut origins = Origins {
+        observer,
+        origins: HashMap::new(),
+    };
+    let value = _evaluate(expr, &mut HashMap::new(), &mut origins)?;
+    Ok((value, origins.origins))
+}
+
+/// Hooks into the evaluation, used to collect statistics about it or to stop it early.
+trait Observer<V: Label> {
+    /// The evaluation takes a step, such as looking up a variable or returning a value, whether
+    /// or not it β-reduces anything.
+    fn step(&mut self) -> Result<(), LambdaError> {
+        Ok(())
+    }
+
+    /// A variable is looked up in the bindings.
+    fn lookup(&mut self, _bound: bool) {}
+
+    /// A function is applied to the evaluated argument `arg`.
+    fn beta(&mut self, _arg: &Expression<V>) -> Result<(), LambdaError> {
+        Ok(())
+    }
+
+    /// The body of an applied function evaluated to `value`, which is about to be evaluated
+    /// again, with `depth` frames waiting for the result.
+    fn reduced(&mut self, _value: &Expression<V>, _depth: usize) -> Result<(), LambdaError> {
+        Ok(())
+    }
+
+    /// A value is returned to a frame, leaving `depth` frames.
+    fn returned(&mut self, _depth: usize) {}
+
+    /// A function copied out of the bindings has its parameter `param` renamed to `name`.
+    fn renamed(&mut self, _name: &V, _param: &V) {}
+}
+
+impl<V: Label> Observer<V> for () {}
+
+/// Remembers the function of the input that each renamed copy of a function came from, and
+/// passes everything else on to another observer.
+struct Origins<'a, V: Label, O> {
+    observer: &'a mut O,
+    origins: HashMap<V, V>,
+}
+
+impl<V: Label, O: Observer<V>> Observer<V> for Origins<'_, V, O> {
+    fn step(&mut self) -> Result<(), LambdaError> {
+        self.observer.step()
+    }
+
+    fn lookup(&mut self, bound: bool) {
+        self.observer.lookup(bound);
+    }
+
+    fn beta(&mut self, arg: &Expression<V>) -> Result<(), LambdaError> {
+        self.observer.beta(arg)
+    }
+
+    fn reduced(&mut self, value: &Expression<V>, depth: usize) -> Result<(), LambdaError> {
+        self.observer.reduced(value, depth)
+    }
+
+    fn returned(&mut self, depth: usize) {
+        self.observer.returned(depth);
+    }
+
+    fn renamed(&mut self, name: &V, param: &V) {
+        // Copies are made of copies, so follow them back to the input.
+        let origin = self.origins.get(param).unwrap_or(param).clone();
+        self.origins.insert(name.clone(), origin);
+    }
+}
+
+/// Detects β-reductions that repeat a term recently reduced in the same place.
+struct LoopDetector<V: Label> {
+    /// For each depth with reductions in progress, the latest terms reduced there, most recent
+    /// last. Sorted by depth.
+    history: Vec<(usize, VecDeque<Expression<V>>)>,
+}
+
+impl<V: Label> LoopDetector<V> {
+    /// The number of terms remembered at each depth, i.e. the longest period found.
+    const PERIOD: usize = 8;
+}
+
+impl<V: Label> Default for LoopDetector<V> {
+    fn default() -> Self {
+        Self {
+            history: Vec::new(),
         }
-        Expression::Function(param, body) => {
-            Expression::new_function(param, _evaluate(*body, bindings))
+    }
+}
+
+impl<V: Label + fmt::Display> Observer<V> for LoopDetector<V> {
+    fn reduced(&mut self, value: &Expression<V>, depth: usize) -> Result<(), LambdaError> {
+        // Every frame below `depth` is unchanged since the earlier terms were reduced, so if the
+        // same term comes up again, evaluating it will keep doing the same thing forever.
+        if self.history.last().map(|(d, _)| *d) != Some(depth) {
+            self.history.push((depth, VecDeque::new()));
+        }
+        let (_, terms) = self.history.last_mut().unwrap();
+        if let Some(period) = terms
+            .iter()
+            .rev()
+            .position(|term| alpha_equivalent(term, value))
+        {
+            return Err(LambdaError::RuntimeError(format!(
+                "Evaluation loops: {} reduces back to itself after {} β-reduction{}.",
+                value,
+                period + 1,
+                if period == 0 { "" } else { "s" }
+            )));
         }
-        Expression::Application(lhs, rhs) => {
-            let rhs = _evaluate(*rhs, bindings);
-
-            // If the lhs is a function, we want to apply it to the rhs. However, the lhs might
-            // not _yet_ be a function, and will only reduce to a function after being evaluated.
-            // If and only if it is an application, we evaluate the lhs first.
-            let lhs = match *lhs {
-                Expression::Application(_, _) => _evaluate(*lhs, bindings),
-                _ => *lhs,
-            };
-
-            match lhs {
-                Expression::Function(param, body) => {
-                    // Perform β-reduction, i.e. apply the lhs with the rhs as the argument.
-                    bindings.insert(param.clone(), rhs);
-                    let result = _evaluate(*body, bindings);
-                    bindings.remove(param.as_str());
+        if terms.len() == Self::PERIOD {
+            terms.pop_front();
+        }
+        terms.push_back(value.clone());
+        Ok(())
+    }
+
+    fn returned(&mut self, depth: usize) {
+        // Returning below a depth finishes the reductions there, so they can't be repeated.
+        while matches!(self.history.last(), Some((d, _)) if *d > depth) {
+            self.history.pop();
+        }
+    }
+}
+
+/// Stops the evaluation when a token is cancelled.
+struct Cancellation<'a> {
+    token: &'a CancellationToken,
+    /// The number of steps of the evaluation so far.
+    steps: usize,
+    /// The number of β-reductions so far.
+    beta_steps: usize,
+}
+
+impl Cancellation<'_> {
+    /// The number of steps of the evaluation between checks of the token.
+    const INTERVAL: usize = 1024;
+}
+
+impl<V: Label> Observer<V> for Cancellation<'_> {
+    fn step(&mut self) -> Result<(), LambdaError> {
+        self.steps += 1;
+        if self.steps % Self::INTERVAL != 0 {
+            Ok(())
+        } else if self.token.was_cancelled() {
+            Err(LambdaError::RuntimeError(format!(
+                "Evaluation cancelled after {} β-reductions.",
+                self.beta_steps
+            )))
+        } else if self.token.has_timed_out() {
+            Err(LambdaError::RuntimeError(format!(
+                "Evaluation timed out after {} β-reductions.",
+                self.beta_steps
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn beta(&mut self, _arg: &Expression<V>) -> Result<(), LambdaError> {
+        self.beta_steps += 1;
+        Ok(())
+    }
+}
+
+/// Stops the evaluation after a number of β-reductions.
+struct StepLimit {
+    max_steps: usize,
+    steps: usize,
+}
+
+impl<V: Label> Observer<V> for StepLimit {
+    fn beta(&mut self, _arg: &Expression<V>) -> Result<(), LambdaError> {
+        if self.steps == self.max_steps {
+            return Err(LambdaError::RuntimeError(format!(
+                "Evaluation stopped after the maximum of {} β-reductions.",
+                self.max_steps
+            )));
+        }
+        self.steps += 1;
+        Ok(())
+    }
+}
+
+/// A pending step in the evaluation, waiting for the value of a subexpression.
+enum Frame<V: Label> {
+    /// The body of a function is being evaluated.
+    Function(V),
+    /// The argument of an application is being evaluated. Holds the unevaluated lhs.
+    Argument(Expression<V>),
+    /// The lhs of an application is being evaluated. Holds the evaluated rhs.
+    Apply(Expression<V>),
+    /// The body of a function applied to an argument bound to the parameter is being evaluated.
+    Reduce(V),
+    /// The lhs of an application that can't be β-reduced is being evaluated. Holds the rhs.
+    Abstract(Expression<V>),
+}
+
+/// The next thing to do: either evaluate an expression, or pass a value to the top frame.
+enum Next<V: Label> {
+    Evaluate(Expression<V>),
+    Return(Expression<V>),
+}
+
+fn _evaluate<V: Label>(
+    expr: Expression<V>,
+    bindings: &mut HashMap<V, Expression<V>>,
+    observer: &mut impl Observer<V>,
+) -> Result<Expression<V>, LambdaError> {
+    let mut fresh_names = unused_names(&expr);
+    // The evaluation uses an explicit stack of frames rather than recursion, so that deeply
+    // nested expressions can be evaluated without overflowing the stack.
+    let mut frames = Vec::new();
+    let mut next = Next::Evaluate(expr);
+    loop {
+        observer.step()?;
+        next = match next {
+            Next::Evaluate(expr) => match expr.into_parts() {
+                Parts::Variable(label) => {
+                    // If the variable is bound, return the bound value, otherwise just return the variable.
+                    let value = bindings.get(&label);
+                    observer.lookup(value.is_some());
+                    Next::Return(match value {
+                        // Every copy of a function gets new names for its parameters, so that no
+                        // function shadows another, and bindings of the same name never nest.
+                        Some(value @ Expression::Variable(_)) => value.clone(),
+                        Some(value) => rename_functions(
+                            value.clone(),
+                            &mut fresh_names,
+                            &mut |name, param| observer.renamed(name, param),
+                        )?,
+                        None => Expression::Variable(label),
+                    })
+                }
+                Parts::Function(param, body) => {
+                    frames.push(Frame::Function(param));
+                    Next::Evaluate(body)
+                }
+                Parts::Application(lhs, rhs) => {
+                    frames.push(Frame::Argument(lhs));
+                    Next::Evaluate(rhs)
+                }
+            },
+            Next::Return(value) => match frames.pop() {
+                None => return Ok(value),
+                Some(Frame::Function(param)) => {
+                    observer.returned(frames.len());
+                    Next::Return(Expression::new_function(param, value))
+                }
+                Some(Frame::Argument(lhs)) => {
+                    observer.returned(frames.len());
+                    // If the lhs is a function, we want to apply it to the rhs. However, the lhs might
+                    // not _yet_ be a function, and will only reduce to a function after being evaluated.
+                    // If and only if it is an application, we evaluate the lhs first.
+                    if let Expression::Application(_, _) = lhs {
+                        frames.push(Frame::Apply(value));
+                        Next::Evaluate(lhs)
+                    } else {
+                        apply(lhs, value, bindings, &mut frames, observer)?
+                    }
+                }
+                Some(Frame::Apply(rhs)) => {
+                    observer.returned(frames.len());
+                    apply(value, rhs, bindings, &mut frames, observer)?
+                }
+                Some(Frame::Reduce(param)) => {
+                    bindings.remove(&param);
+                    observer.returned(frames.len());
+                    observer.reduced(&value, frames.len())?;
 
                     // At this point we have performed β-reduction. It might still be that the result
                     // is an application of a function, which could be further reduced. We therefore,
-                    // recursively evaluate the result once more. Note that if a function application
+                    // evaluate the result once more. Note that if a function application
                     // evaluates to itself, this would become an infinite loop. Evaluating such an
-                    // expression would be an infinite computation, so crashing here seems reasonable.
-                    // Could possibly try to detect this and just return the expression.
-                    _evaluate(result, bindings)
+                    // expression would be an infinite computation, so looping here seems reasonable.
+                    // `evaluate_checked` detects the simplest of these loops.
+                    Next::Evaluate(value)
                 }
-                _ => {
-                    // The application is abstract, so can't be β-reduced. In this case we just
-                    // evaluate the lhs as well and return the abstract application.
-                    let lhs = _evaluate(lhs, bindings);
-                    Expression::new_application(lhs, rhs)
+                Some(Frame::Abstract(rhs)) => {
+                    observer.returned(frames.len());
+                    Next::Return(Expression::new_application(value, rhs))
                 }
-            }
+            },
         }
     }
 }
+
+/// Apply an evaluated lhs to an evaluated rhs.
+fn apply<V: Label>(
+    lhs: Expression<V>,
+    rhs: Expression<V>,
+    bindings: &mut HashMap<V, Expression<V>>,
+    frames: &mut Vec<Frame<V>>,
+    observer: &mut impl Observer<V>,
+) -> Result<Next<V>, LambdaError> {
+    if let Expression::Function(_, _) = lhs {
+        let Parts::Function(param, body) = lhs.into_parts() else {
+            unreachable!()
+        };
+        // Perform β-reduction, i.e. apply the lhs with the rhs as the argument.
+        observer.beta(&rhs)?;
+        bindings.insert(param.clone(), rhs);
+        frames.push(Frame::Reduce(param));
+        Ok(Next::Evaluate(body))
+    } else {
+        // The application is abstract, so can't be β-reduced. In this case we just
+        // evaluate the lhs as well and return the abstract application.
+        frames.push(Frame::Abstract(rhs));
+        Ok(Next::Evaluate(lhs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol::Symbol;
+
+    fn parse(code: &str) -> Expression {
+        code.parse().unwrap()
+    }
+
+    #[test]
+    fn loop_with_longer_period() {
+        let mut detector = LoopDetector::default();
+        assert!(detector.reduced(&parse("a b"), 3).is_ok());
+        assert!(detector.reduced(&parse("λx.x"), 3).is_ok());
+        assert!(detector.reduced(&parse("b a"), 3).is_ok());
+        let error = detector.reduced(&parse("λy.y"), 3).unwrap_err();
+        assert!(
+            error.to_string().contains("after 2 β-reductions"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn returning_forgets_reductions() {
+        let mut detector = LoopDetector::default();
+        assert!(detector.reduced(&parse("a"), 3).is_ok());
+        assert!(detector.reduced(&parse("b"), 5).is_ok());
+        Observer::<Symbol>::returned(&mut detector, 3);
+        assert!(detector.reduced(&parse("b"), 5).is_ok());
+        Observer::<Symbol>::returned(&mut detector, 2);
+        assert!(detector.reduced(&parse("a"), 3).is_ok());
+    }
+}
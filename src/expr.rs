@@ -1,40 +1,303 @@
-use crate::evaluate::evaluate;
+use crate::evaluate::{evaluate, evaluate_with_stats, Stats};
+use crate::graph::{reduction_graph, ReductionGraph};
+use crate::label::Label;
+use crate::lazy::evaluate_lazy;
+use crate::metrics::Metrics;
+use crate::nbe::evaluate_nbe;
 use crate::normalize::normalize_variables;
+use crate::readable::evaluate_readable;
+use crate::redex::{contract_at, redex_positions, subexpression, Direction, Path};
+use crate::reduction::{hnf, whnf};
+use crate::symbol::Symbol;
+use crate::trace::{trace, Trace};
+use crate::visit::{BoundVariables, Depth, FreeVariables, Occurrences, Size};
 use std::fmt;
+use std::mem::{self, ManuallyDrop};
+use std::ptr;
 
 /// An expression in the lambda calculus.
 ///
 /// This represents a lambda term, and is either a variable, a function (abstraction) or an application.
-#[derive(Debug, Clone)]
-pub enum Expression {
-    Variable(String),
-    Function(String, Box<Expression>),
-    Application(Box<Expression>, Box<Expression>),
+///
+/// Variables are labelled by interned `Symbol`s by default, which is what parsing produces, but
+/// any `Label` type can be used instead. `map_labels` converts between label types.
+///
+/// Deeply nested expressions are supported: cloning, comparing, displaying and dropping an
+/// expression all use an explicit stack rather than recursion.
+#[derive(Debug)]
+pub enum Expression<V: Label = Symbol> {
+    Variable(V),
+    Function(V, Box<Expression<V>>),
+    Application(Box<Expression<V>>, Box<Expression<V>>),
 }
 
-impl Expression {
-    pub fn new_variable(label: &str) -> Self {
-        Expression::Variable(label.to_string())
+impl<V: Label> Expression<V> {
+    pub fn new_variable(label: impl Into<V>) -> Self {
+        Expression::Variable(label.into())
     }
 
-    pub fn new_function(param: String, body: Expression) -> Self {
-        Expression::Function(param, Box::new(body))
+    pub fn new_function(param: impl Into<V>, body: Expression<V>) -> Self {
+        Expression::Function(param.into(), Box::new(body))
     }
 
-    pub fn new_application(lhs: Expression, rhs: Expression) -> Self {
+    pub fn new_application(lhs: Expression<V>, rhs: Expression<V>) -> Self {
         Expression::Application(Box::new(lhs), Box::new(rhs))
     }
 
-    pub fn evaluate(self) -> Expression {
+    pub fn evaluate(self) -> Expression<V> {
         evaluate(self)
     }
 
-    pub fn normalize(self) -> Expression {
+    pub fn evaluate_with_stats(self) -> (Expression<V>, Stats) {
+        evaluate_with_stats(self)
+    }
+
+    pub fn whnf(self) -> Expression<V> {
+        whnf(self)
+    }
+
+    pub fn hnf(self) -> Expression<V> {
+        hnf(self)
+    }
+
+    pub fn trace(self, max_steps: usize) -> Trace<V> {
+        trace(self, max_steps)
+    }
+
+    pub fn reduction_graph(self, max_size: usize) -> ReductionGraph<V> {
+        reduction_graph(self, max_size)
+    }
+
+    /// The positions of the β-redexes in the expression, leftmost outermost first.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::Expression;
+    ///
+    /// let expr: Expression = "(λx.x) ((λy.y) z)".parse().unwrap();
+    /// let positions = expr.redex_positions();
+    /// assert_eq!(positions.len(), 2);
+    /// assert_eq!(expr.subexpression(&positions[1]).unwrap().to_string(), "(λy.y) z");
+    /// assert_eq!(expr.contract_at(&positions[1]).unwrap().to_string(), "(λx.x) z");
+    /// ```
+    pub fn redex_positions(&self) -> Vec<Path> {
+        redex_positions(self)
+    }
+
+    pub fn subexpression(&self, path: &[Direction]) -> Option<&Expression<V>> {
+        subexpression(self, path)
+    }
+
+    pub fn contract_at(self, path: &[Direction]) -> Option<Expression<V>> {
+        contract_at(self, path)
+    }
+
+    pub fn normalize(self) -> Expression<V> {
         normalize_variables(self)
     }
+
+    /// The variables that occur free in the expression, in the order they first occur.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::Expression;
+    ///
+    /// let expr: Expression = "λx.f x y".parse().unwrap();
+    /// assert_eq!(expr.free_variables(), ["f", "y"]);
+    /// ```
+    pub fn free_variables(&self) -> Vec<V> {
+        FreeVariables::of(self)
+    }
+
+    /// The parameters of the functions in the expression, in the order they first occur.
+    pub fn bound_variables(&self) -> Vec<V> {
+        BoundVariables::of(self)
+    }
+
+    /// Check that the expression has no free variables.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::{church, Expression};
+    ///
+    /// assert!(church::succ().is_closed());
+    /// assert!(!"λx.f x".parse::<Expression>().unwrap().is_closed());
+    /// ```
+    pub fn is_closed(&self) -> bool {
+        self.free_variables().is_empty()
+    }
+
+    /// The number of free occurrences of the variable `name` in the expression.
+    pub fn occurrences(&self, name: impl Into<V>) -> usize {
+        Occurrences::of(name, self)
+    }
+
+    /// The number of variables, functions and applications in the expression.
+    pub fn size(&self) -> usize {
+        Size::of(self)
+    }
+
+    /// The number of parts on the longest path from the expression down to a variable.
+    pub fn depth(&self) -> usize {
+        Depth::of(self)
+    }
+
+    /// The number of functions in the expression.
+    pub fn abstractions(&self) -> usize {
+        self.metrics().abstractions
+    }
+
+    /// The number of applications in the expression.
+    pub fn applications(&self) -> usize {
+        self.metrics().applications
+    }
+
+    /// The number of β-redexes in the expression, i.e. functions applied to an argument.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::Expression;
+    ///
+    /// let expr: Expression = "(λx.x) ((λy.y) z) (f z)".parse().unwrap();
+    /// assert_eq!(expr.redexes(), 2);
+    /// ```
+    pub fn redexes(&self) -> usize {
+        self.metrics().redexes
+    }
+
+    /// All the metrics of the expression, measured at once.
+    pub fn metrics(&self) -> Metrics {
+        Metrics::of(self)
+    }
+
+    /// Convert the expression to another label type, by relabelling every variable.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::{Expression, Symbol};
+    ///
+    /// let expr: Expression = "λx.x y".parse().unwrap();
+    /// let expr: Expression<String> = expr.map_labels(|label| label.as_str().to_uppercase());
+    /// assert_eq!(expr.to_string(), "λX.X Y");
+    /// ```
+    pub fn map_labels<W: Label>(self, mut f: impl FnMut(V) -> W) -> Expression<W> {
+        enum Task<V: Label> {
+            Visit(Expression<V>),
+            Function(V),
+            Application,
+        }
+
+        let mut tasks = vec![Task::Visit(self)];
+        let mut results = Vec::new();
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit(expr) => match expr.into_parts() {
+                    Parts::Variable(label) => results.push(Expression::Variable(f(label))),
+                    Parts::Function(param, body) => {
+                        tasks.push(Task::Function(param));
+                        tasks.push(Task::Visit(body));
+                    }
+                    Parts::Application(lhs, rhs) => {
+                        tasks.push(Task::Application);
+                        tasks.push(Task::Visit(rhs));
+                        tasks.push(Task::Visit(lhs));
+                    }
+                },
+                Task::Function(param) => {
+                    let body = results.pop().unwrap();
+                    results.push(Expression::new_function(f(param), body));
+                }
+                Task::Application => {
+                    let rhs = results.pop().unwrap();
+                    let lhs = results.pop().unwrap();
+                    results.push(Expression::new_application(lhs, rhs));
+                }
+            }
+        }
+        results.pop().unwrap()
+    }
+
+    /// Take the expression apart, moving out its label and subexpressions.
+    ///
+    /// Expressions implement `Drop`, so their fields can't be moved out of by pattern matching.
+    /// Match on the parts instead.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::{Expression, Parts};
+    ///
+    /// let expr: Expression = "λx.x y".parse().unwrap();
+    /// let Parts::Function(param, body) = expr.into_parts() else {
+    ///     panic!("expected a function");
+    /// };
+    /// assert_eq!(param.as_str(), "x");
+    /// assert_eq!(body.to_string(), "x y");
+    /// ```
+    pub fn into_parts(self) -> Parts<V> {
+        // The fields are moved out, so the expression must not be dropped.
+        let expr = ManuallyDrop::new(self);
+        // SAFETY: every field of `expr` is read exactly once, and `expr` is never used again, so
+        // each field ends up with a single owner.
+        unsafe {
+            match &*expr {
+                Expression::Variable(label) => Parts::Variable(ptr::read(label)),
+                Expression::Function(param, body) => {
+                    Parts::Function(ptr::read(param), *ptr::read(body))
+                }
+                Expression::Application(lhs, rhs) => {
+                    Parts::Application(*ptr::read(lhs), *ptr::read(rhs))
+                }
+            }
+        }
+    }
 }
 
-impl std::ops::Mul for Expression {
+impl Expression {
+    pub fn evaluate_lazy(self) -> Expression {
+        evaluate_lazy(self)
+    }
+
+    pub fn evaluate_nbe(self) -> Expression {
+        evaluate_nbe(self)
+    }
+
+    pub fn evaluate_readable(self) -> Expression {
+        evaluate_readable(self)
+    }
+}
+
+impl From<Expression<Symbol>> for Expression<String> {
+    fn from(expr: Expression<Symbol>) -> Self {
+        expr.map_labels(|label| label.as_str().to_string())
+    }
+}
+
+impl From<Expression<String>> for Expression<Symbol> {
+    fn from(expr: Expression<String>) -> Self {
+        expr.map_labels(|label| Symbol::new(&label))
+    }
+}
+
+/// The parts of an expression, as returned by `Expression::into_parts`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Parts<V: Label = Symbol> {
+    Variable(V),
+    Function(V, Expression<V>),
+    Application(Expression<V>, Expression<V>),
+}
+
+/// The first label in an expression, found by following the lhs of applications.
+fn first_label<V: Label>(expr: &Expression<V>) -> &V {
+    let mut expr = expr;
+    loop {
+        match expr {
+            Expression::Variable(label) | Expression::Function(label, _) => return label,
+            Expression::Application(lhs, _) => expr = lhs,
+        }
+    }
+}
+
+impl<V: Label> std::ops::Mul for Expression<V> {
     type Output = Self;
 
     fn mul(self, other: Self) -> Self {
@@ -42,38 +305,113 @@ impl std::ops::Mul for Expression {
     }
 }
 
-impl fmt::Display for Expression {
+impl<V: Label> Clone for Expression<V> {
+    fn clone(&self) -> Self {
+        self.fold(
+            |label| Expression::Variable(label.clone()),
+            |param, body| Expression::Function(param.clone(), Box::new(body)),
+            Expression::new_application,
+        )
+    }
+}
+
+impl<V: Label> Drop for Expression<V> {
+    fn drop(&mut self) {
+        // Move all nested subexpressions onto a stack, so that each one is dropped after its
+        // own subexpressions have been moved out of it, and the default drop never recurses.
+        // Each subexpression moved out is replaced with a variable, labelled by a copy of the
+        // first label found.
+        fn take_children<V: Label>(
+            expr: &mut Expression<V>,
+            placeholder: &mut Option<V>,
+            stack: &mut Vec<Expression<V>>,
+        ) {
+            let children = match expr {
+                Expression::Variable(_) => return,
+                Expression::Function(_, body) => [Some(body), None],
+                Expression::Application(lhs, rhs) => [Some(lhs), Some(rhs)],
+            };
+            for child in children.into_iter().flatten() {
+                if !matches!(child.as_ref(), Expression::Variable(_)) {
+                    let label = placeholder.get_or_insert_with(|| first_label(child).clone());
+                    stack.push(mem::replace(child, Expression::Variable(label.clone())));
+                }
+            }
+        }
+
+        let mut placeholder = None;
+        let mut stack = Vec::new();
+        take_children(self, &mut placeholder, &mut stack);
+        while let Some(mut expr) = stack.pop() {
+            take_children(&mut expr, &mut placeholder, &mut stack);
+        }
+    }
+}
+
+impl<V: Label + fmt::Display> fmt::Display for Expression<V> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Expression::Variable(label) => write!(f, "{}", label),
-            Expression::Function(param, body) => write!(f, "λ{}.{}", param, body),
-            Expression::Application(lhs, rhs) => match lhs.as_ref() {
-                Expression::Variable(_) => match rhs.as_ref() {
-                    Expression::Variable(_) => write!(f, "{} {}", lhs, rhs),
-                    _ => write!(f, "{} ({})", lhs, rhs),
-                },
-                _ => match rhs.as_ref() {
-                    Expression::Variable(_) => write!(f, "({}) {}", lhs, rhs),
-                    _ => write!(f, "({}) ({})", lhs, rhs),
-                },
-            },
+        enum Item<'a, V: Label> {
+            Expr(&'a Expression<V>),
+            Text(&'static str),
+        }
+
+        // Items are pushed in reverse order, so that they are written in the right order.
+        fn push_operand<'a, V: Label>(stack: &mut Vec<Item<'a, V>>, expr: &'a Expression<V>) {
+            match expr {
+                Expression::Variable(_) => stack.push(Item::Expr(expr)),
+                _ => stack.extend([Item::Text(")"), Item::Expr(expr), Item::Text("(")]),
+            }
+        }
+
+        let mut stack = vec![Item::Expr(self)];
+        while let Some(item) = stack.pop() {
+            match item {
+                Item::Text(text) => f.write_str(text)?,
+                Item::Expr(Expression::Variable(label)) => write!(f, "{}", label)?,
+                Item::Expr(Expression::Function(param, body)) => {
+                    write!(f, "λ{}.", param)?;
+                    stack.push(Item::Expr(body));
+                }
+                Item::Expr(Expression::Application(lhs, rhs)) => {
+                    push_operand(&mut stack, rhs);
+                    stack.push(Item::Text(" "));
+                    push_operand(&mut stack, lhs);
+                }
+            }
         }
+        Ok(())
     }
 }
 
-impl PartialEq for Expression {
+impl<V: Label> PartialEq for Expression<V> {
     fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Expression::Variable(lhs), Expression::Variable(rhs)) => lhs == rhs,
-            (
-                Expression::Function(lhs_param, lhs_body),
-                Expression::Function(rhs_param, rhs_body),
-            ) => lhs_param == rhs_param && lhs_body == rhs_body,
-            (
-                Expression::Application(lhs_lhs, lhs_rhs),
-                Expression::Application(rhs_lhs, rhs_rhs),
-            ) => lhs_lhs == rhs_lhs && lhs_rhs == rhs_rhs,
-            _ => false,
+        let mut stack = vec![(self, other)];
+        while let Some(pair) = stack.pop() {
+            match pair {
+                (Expression::Variable(lhs), Expression::Variable(rhs)) => {
+                    if lhs != rhs {
+                        return false;
+                    }
+                }
+                (
+                    Expression::Function(lhs_param, lhs_body),
+                    Expression::Function(rhs_param, rhs_body),
+                ) => {
+                    if lhs_param != rhs_param {
+                        return false;
+                    }
+                    stack.push((lhs_body, rhs_body));
+                }
+                (
+                    Expression::Application(lhs_lhs, lhs_rhs),
+                    Expression::Application(rhs_lhs, rhs_rhs),
+                ) => {
+                    stack.push((lhs_rhs, rhs_rhs));
+                    stack.push((lhs_lhs, rhs_lhs));
+                }
+                _ => return false,
+            }
         }
+        true
     }
 }
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+use crate::dot::escape;
+use crate::expr::Expression;
+use crate::label::Label;
+use crate::normalize::alpha_equivalent;
+use crate::redex::{contract_at, redex_positions, Direction, Path};
+
+/// Every term an expression can be reduced to, by contracting its β-redexes in any order.
+///
+/// Terms that are α-equivalent are the same node, so a term that reduces back to itself makes a
+/// cycle. See `reduction_graph`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReductionGraph<V: Label> {
+    terms: Vec<Expression<V>>,
+    /// An edge for each redex of each term that was explored: the term, the position of the
+    /// redex in it, and the term it reduces to.
+    edges: Vec<(usize, Path, usize)>,
+    complete: bool,
+}
+
+/// Build the reduction graph of an expression: every term it reduces to by contracting any of
+/// its β-redexes, then any of the redexes of those terms, and so on.
+///
+/// Terms larger than `max_size` are left out of the graph, along with the terms only reachable
+/// through them, so that the graph is finite even when the expression has no normal form.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{reduction_graph, Expression};
+///
+/// let expr: Expression = "(λx.λy.y) ((λz.z) w)".parse().unwrap();
+/// let graph = reduction_graph(expr, 100);
+/// let terms: Vec<String> = graph.terms().iter().map(|term| term.to_string()).collect();
+/// assert_eq!(terms, ["(λx.λy.y) ((λz.z) w)", "λy.y", "(λx.λy.y) w"]);
+/// assert_eq!(graph.edges().collect::<Vec<_>>(), [(0, 1), (0, 2), (2, 1)]);
+/// assert_eq!(graph.normal_forms().collect::<Vec<_>>(), [1]);
+/// assert!(graph.is_complete());
+/// ```
+pub fn reduction_graph<V: Label>(expr: Expression<V>, max_size: usize) -> ReductionGraph<V> {
+    let mut graph = ReductionGraph {
+        terms: vec![expr],
+        edges: Vec::new(),
+        complete: true,
+    };
+    // The terms of each size, to only compare terms that could be α-equivalent.
+    let mut by_size: HashMap<usize, Vec<usize>> = HashMap::new();
+    by_size.insert(graph.terms[0].size(), vec![0]);
+    let mut next = 0;
+    while next < graph.terms.len() {
+        for path in redex_positions(&graph.terms[next]) {
+            let reduct =
+                contract_at(graph.terms[next].clone(), &path).expect("the path leads to a redex");
+            let size = reduct.size();
+            if size > max_size {
+                graph.complete = false;
+                continue;
+            }
+            let same_size = by_size.entry(size).or_default();
+            let target = match same_size
+                .iter()
+                .find(|&&i| alpha_equivalent(&graph.terms[i], &reduct))
+            {
+                Some(&i) => i,
+                None => {
+                    same_size.push(graph.terms.len());
+                    graph.terms.push(reduct);
+                    graph.terms.len() - 1
+                }
+            };
+            graph.edges.push((next, path, target));
+        }
+        next += 1;
+    }
+    graph
+}
+
+impl<V: Label> ReductionGraph<V> {
+    /// The terms of the graph, starting with the expression it was built from.
+    pub fn terms(&self) -> &[Expression<V>] {
+        &self.terms
+    }
+
+    /// The index in `terms` of a term α-equivalent to `term`, if it is in the graph.
+    pub fn index_of(&self, term: &Expression<V>) -> Option<usize> {
+        self.terms
+            .iter()
+            .position(|other| alpha_equivalent(other, term))
+    }
+
+    /// The β-reductions between the terms, as pairs of indices into `terms`.
+    ///
+    /// There is an edge for each redex, so a term with two redexes that reduce to the same term
+    /// has two edges to it.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.edges.iter().map(|&(from, _, to)| (from, to))
+    }
+
+    /// The β-reductions between the terms, along with the position of the redex contracted in
+    /// the first term.
+    pub fn reductions(&self) -> impl Iterator<Item = (usize, &[Direction], usize)> + '_ {
+        self.edges
+            .iter()
+            .map(|(from, path, to)| (*from, path.as_slice(), *to))
+    }
+
+    /// The indices of the terms in normal form, i.e. without any redexes.
+    ///
+    /// By the Church–Rosser theorem, there is at most one.
+    pub fn normal_forms(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.terms.len()).filter(|&i| redex_positions(&self.terms[i]).is_empty())
+    }
+
+    /// If no term was left out for being larger than the maximum size.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+}
+
+impl<V: Label + fmt::Display> ReductionGraph<V> {
+    /// Render the graph in the Graphviz DOT language, with each term in a box, and the normal
+    /// form in a double box.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::Expression;
+    ///
+    /// let expr: Expression = "(λx.x) y".parse().unwrap();
+    /// assert_eq!(
+    ///     expr.reduction_graph(10).to_dot(),
+    ///     r#"digraph {
+    ///     node [shape=box];
+    ///     n0 [label="(λx.x) y"];
+    ///     n1 [label="y", peripheries=2];
+    ///     n0 -> n1;
+    /// }
+    /// "#
+    /// );
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n    node [shape=box];\n");
+        let normal_forms: Vec<usize> = self.normal_forms().collect();
+        for (i, term) in self.terms.iter().enumerate() {
+            let style = if normal_forms.contains(&i) {
+                ", peripheries=2"
+            } else {
+                ""
+            };
+            writeln!(
+                dot,
+                "    n{} [label=\"{}\"{}];",
+                i,
+                escape(&term.to_string()),
+                style
+            )
+            .unwrap();
+        }
+        for (from, to) in self.edges() {
+            writeln!(dot, "    n{} -> n{};", from, to).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
@@ -0,0 +1,563 @@
+//! Experimental optimal reduction using interaction nets.
+//!
+//! An expression is translated to a sharing graph in the style of Lamping's algorithm: a net
+//! of abstraction and application nodes, plus indexed fans (which share a term between several
+//! uses) and the brackets and croissants that keep track of which fans belong together.
+//! Reduction rewrites pairs of nodes connected through their principal ports. A fan copies a
+//! term one node at a time and only as far as it is needed, so the work inside a shared
+//! argument is done at most once. For terms with a lot of sharing this can be exponentially
+//! faster than `evaluate`.
+//!
+//! The index of a node is the number of arguments it is nested inside of. Fans only
+//! annihilate with fans of the same index, which is what makes it possible to copy a term
+//! that itself shares something. Brackets and croissants adjust the indices of the nodes they
+//! pass through as terms move in and out of arguments.
+//!
+//! This is an experiment to compare against the other evaluators. In particular it does no
+//! garbage collection of control nodes, so they can accumulate on some terms.
+use std::collections::HashMap;
+
+use crate::error::LambdaError;
+use crate::expr::Expression;
+use crate::normalize::normalize_variables;
+use crate::readable::rename;
+use crate::symbol::Symbol;
+
+/// Evaluate an expression by optimal reduction of an interaction net, and return the
+/// normalized result.
+///
+/// Whenever `evaluate` finishes, the result is the same. An error is returned if the reduced
+/// net can't be read back into an expression, which would be a bug in the reduction.
+///
+/// # Examples
+/// ```rust
+/// use lambda::church;
+///
+/// let expr = church::pow() * church::two() * (church::pow() * church::two() * church::two());
+/// let sixteen = lambda::evaluate_optimal(expr.clone()).unwrap();
+/// assert_eq!(sixteen, expr.evaluate());
+/// ```
+pub fn evaluate_optimal(expr: Expression) -> Result<Expression, LambdaError> {
+    let (result, _) = evaluate_with_origins(&expr)?;
+    Ok(normalize_variables(result))
+}
+
+/// Evaluate an expression like `evaluate_optimal`, and name the functions of the result after
+/// the parameters they came from in the input, like `evaluate_readable`.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{evaluate_optimal_readable, Expression};
+///
+/// let expr: Expression = "(λf.λx.f x) (λy.y)".parse().unwrap();
+/// assert_eq!(evaluate_optimal_readable(expr).unwrap().to_string(), "λx.x");
+/// ```
+pub fn evaluate_optimal_readable(expr: Expression) -> Result<Expression, LambdaError> {
+    let (result, origins) = evaluate_with_origins(&expr)?;
+    Ok(rename(result, |name| origins.get(name).copied()))
+}
+
+/// Evaluate an expression, giving every function of the result a fresh name, and return it
+/// along with the parameter in `expr` that each of those functions came from.
+fn evaluate_with_origins(
+    expr: &Expression,
+) -> Result<(Expression, HashMap<Symbol, Symbol>), LambdaError> {
+    let mut net = Net::default();
+    let root = net.new_node(Kind::Root);
+    net.encode(expr, Port::new(root, 0), 0, &mut HashMap::new());
+
+    let mut reader = Reader::default();
+    let result = reader.read(&mut net, Port::new(root, 0), Context::default())?;
+    Ok((result, reader.origins))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Port {
+    node: usize,
+    slot: usize,
+}
+
+impl Port {
+    fn new(node: usize, slot: usize) -> Self {
+        Port { node, slot }
+    }
+
+    fn is_principal(&self) -> bool {
+        self.slot == 0
+    }
+}
+
+/// The kinds of nodes in the net.
+///
+/// Every node has a principal port in slot 0. The auxiliary ports are:
+///  - `Lam`: 1 is the bound variable, 2 the body.
+///  - `App`: 1 is the argument, 2 the result.
+///  - `Fan`: 1 and 2 are the two uses of the shared term.
+///  - `Croissant` and `Bracket`: 1 is the only auxiliary port.
+///
+/// The principal port of a fan, croissant or bracket created by the translation points
+/// towards the function binding the variable it is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Root,
+    Free(usize),
+    Era,
+    /// A function, with the parameter it was built from.
+    Lam(Symbol),
+    App,
+    Fan(usize),
+    Croissant(usize),
+    Bracket(usize),
+}
+
+impl Kind {
+    fn arity(&self) -> usize {
+        match self {
+            Kind::Root | Kind::Free(_) | Kind::Era => 0,
+            Kind::Croissant(_) | Kind::Bracket(_) => 1,
+            Kind::Lam(_) | Kind::App | Kind::Fan(_) => 2,
+        }
+    }
+
+    fn index(&self) -> Option<usize> {
+        match self {
+            Kind::Fan(i) | Kind::Croissant(i) | Kind::Bracket(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// The kind of a copy of this node, after a node of kind `other` has passed through it.
+    fn passed_by(&self, other: Kind) -> Kind {
+        match (*self, other) {
+            (Kind::Fan(i), Kind::Croissant(_)) => Kind::Fan(i - 1),
+            (Kind::Fan(i), Kind::Bracket(_)) => Kind::Fan(i + 1),
+            (Kind::Croissant(i), Kind::Croissant(_)) => Kind::Croissant(i - 1),
+            (Kind::Croissant(i), Kind::Bracket(_)) => Kind::Croissant(i + 1),
+            (Kind::Bracket(i), Kind::Croissant(_)) => Kind::Bracket(i - 1),
+            (Kind::Bracket(i), Kind::Bracket(_)) => Kind::Bracket(i + 1),
+            (kind, _) => kind,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    kind: Kind,
+    ports: [Port; 3],
+}
+
+#[derive(Default)]
+struct Net {
+    nodes: Vec<Node>,
+    reusable: Vec<usize>,
+    free_names: Vec<Symbol>,
+}
+
+impl Net {
+    fn new_node(&mut self, kind: Kind) -> usize {
+        let node = Node {
+            kind,
+            ports: [Port::new(0, 0); 3],
+        };
+        match self.reusable.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn kind(&self, node: usize) -> Kind {
+        self.nodes[node].kind
+    }
+
+    /// The port on the other end of the wire connected to `port`.
+    fn enter(&self, port: Port) -> Port {
+        self.nodes[port.node].ports[port.slot]
+    }
+
+    fn link(&mut self, a: Port, b: Port) {
+        self.nodes[a.node].ports[a.slot] = b;
+        self.nodes[b.node].ports[b.slot] = a;
+    }
+
+    /// Connect `new` to whatever `old` is connected to.
+    fn replace(&mut self, old: Port, new: Port) {
+        let other = self.enter(old);
+        self.link(new, other);
+    }
+
+    /// Connect what `a` is connected to with what `b` is connected to.
+    fn fuse(&mut self, a: Port, b: Port) {
+        let (a, b) = (self.enter(a), self.enter(b));
+        self.link(a, b);
+    }
+
+    /// Build the net for `expr` at the given index, connecting the result to `up`.
+    ///
+    /// Occurrences of bound variables are collected in `scope` along with the index of their
+    /// binder, and connected to it once the body of the function has been built.
+    fn encode(
+        &mut self,
+        expr: &Expression,
+        up: Port,
+        index: usize,
+        scope: &mut HashMap<Symbol, Vec<(usize, Vec<Port>)>>,
+    ) {
+        enum Task<'a> {
+            Encode(&'a Expression, Port, usize),
+            /// Connect the variable of a function to its occurrences in the body.
+            Close(Symbol, usize, usize),
+        }
+
+        let mut tasks = vec![Task::Encode(expr, up, index)];
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Encode(Expression::Variable(label), up, index) => {
+                    match scope.get_mut(label).and_then(|s| s.last_mut()) {
+                        Some((binder_index, occurrences)) => {
+                            // Leave every argument between the occurrence and the binder
+                            // through a bracket, after using the variable through a croissant.
+                            let croissant = self.new_node(Kind::Croissant(index));
+                            self.link(up, Port::new(croissant, 1));
+                            let mut port = Port::new(croissant, 0);
+                            for i in (*binder_index..index).rev() {
+                                let bracket = self.new_node(Kind::Bracket(i));
+                                self.link(port, Port::new(bracket, 1));
+                                port = Port::new(bracket, 0);
+                            }
+                            occurrences.push(port);
+                        }
+                        None => {
+                            self.free_names.push(*label);
+                            let free = self.new_node(Kind::Free(self.free_names.len() - 1));
+                            self.link(up, Port::new(free, 0));
+                        }
+                    }
+                }
+                Task::Encode(Expression::Function(param, body), up, index) => {
+                    let lam = self.new_node(Kind::Lam(*param));
+                    self.link(up, Port::new(lam, 0));
+                    scope.entry(*param).or_default().push((index, Vec::new()));
+                    tasks.push(Task::Close(*param, lam, index));
+                    tasks.push(Task::Encode(body, Port::new(lam, 2), index));
+                }
+                Task::Encode(Expression::Application(lhs, rhs), up, index) => {
+                    let app = self.new_node(Kind::App);
+                    self.link(up, Port::new(app, 2));
+                    tasks.push(Task::Encode(rhs, Port::new(app, 1), index + 1));
+                    tasks.push(Task::Encode(lhs, Port::new(app, 0), index));
+                }
+                Task::Close(param, lam, index) => {
+                    let (_, occurrences) = scope.get_mut(&param).unwrap().pop().unwrap();
+                    self.share(Port::new(lam, 1), &occurrences, index);
+                }
+            }
+        }
+    }
+
+    /// Connect a bound variable to all its occurrences, using a tree of fans.
+    fn share(&mut self, var: Port, occurrences: &[Port], index: usize) {
+        let mut var = var;
+        let mut occurrences = occurrences;
+        while let [first, rest @ ..] = occurrences {
+            if rest.is_empty() {
+                self.link(var, *first);
+                return;
+            }
+            let fan = self.new_node(Kind::Fan(index));
+            self.link(var, Port::new(fan, 0));
+            self.link(Port::new(fan, 1), *first);
+            var = Port::new(fan, 2);
+            occurrences = rest;
+        }
+        let era = self.new_node(Kind::Era);
+        self.link(var, Port::new(era, 0));
+    }
+
+    /// Rewrite the active pair made up of the nodes `a` and `b`.
+    ///
+    /// Returns false if the pair is stuck, i.e. is a free variable in function position.
+    fn interact(&mut self, a: usize, b: usize) -> Result<bool, LambdaError> {
+        match (self.kind(a), self.kind(b)) {
+            (Kind::Lam(_), Kind::App) => self.beta(a, b),
+            (Kind::App, Kind::Lam(_)) => self.beta(b, a),
+            (Kind::App, Kind::Free(_)) | (Kind::Free(_), Kind::App) => return Ok(false),
+            (Kind::Era, _) => self.erase(b),
+            (_, Kind::Era) => self.erase(a),
+            (x, y) if x == y && x.index().is_some() => self.annihilate(a, b),
+            (x, y) => match (x.index(), y.index()) {
+                (Some(i), Some(j)) if i < j => self.commute(a, b),
+                (Some(i), Some(j)) if i > j => self.commute(b, a),
+                (Some(_), None) if y != Kind::Root => self.commute(a, b),
+                (None, Some(_)) if x != Kind::Root => self.commute(b, a),
+                _ => {
+                    return Err(LambdaError::RuntimeError(format!(
+                        "Unexpected interaction between {:?} and {:?} in net.",
+                        x, y
+                    )))
+                }
+            },
+        }
+        self.reusable.extend([a, b]);
+        Ok(true)
+    }
+
+    fn beta(&mut self, lam: usize, app: usize) {
+        self.fuse(Port::new(lam, 1), Port::new(app, 1));
+        self.fuse(Port::new(lam, 2), Port::new(app, 2));
+    }
+
+    fn annihilate(&mut self, a: usize, b: usize) {
+        for slot in 1..=self.kind(a).arity() {
+            self.fuse(Port::new(a, slot), Port::new(b, slot));
+        }
+    }
+
+    /// Let the node `mover` pass through the node `other`.
+    ///
+    /// A copy of `other` is made for each auxiliary port of `mover`, and a copy of `mover` for
+    /// each auxiliary port of `other`. For a fan this is duplication of `other`, and for a
+    /// bracket or croissant it moves `other` in or out of an argument.
+    fn commute(&mut self, mover: usize, other: usize) {
+        let mover_kind = self.kind(mover);
+        let other_kind = self.kind(other).passed_by(mover_kind);
+        let others: Vec<usize> = (1..=mover_kind.arity())
+            .map(|slot| {
+                let copy = self.new_node(other_kind);
+                self.replace(Port::new(mover, slot), Port::new(copy, 0));
+                copy
+            })
+            .collect();
+        for slot in 1..=other_kind.arity() {
+            let copy = self.new_node(mover_kind);
+            self.replace(Port::new(other, slot), Port::new(copy, 0));
+            for (i, other_copy) in others.iter().enumerate() {
+                self.link(Port::new(*other_copy, slot), Port::new(copy, i + 1));
+            }
+        }
+    }
+
+    /// Erase the node `other`, and pass erasers on to whatever it is connected to.
+    fn erase(&mut self, other: usize) {
+        for slot in 1..=self.kind(other).arity() {
+            let copy = self.new_node(Kind::Era);
+            self.replace(Port::new(other, slot), Port::new(copy, 0));
+        }
+    }
+
+    /// Reduce the net until the node connected to `host` is in weak head normal form.
+    ///
+    /// This walks down the spine of applications and through fans and control nodes until it
+    /// finds an active pair, rewrites it, and then continues from the node it came from.
+    fn whnf(&mut self, host: Port) -> Result<(), LambdaError> {
+        let mut path = Vec::new();
+        let mut prev = host;
+        loop {
+            let next = self.enter(prev);
+            if prev.is_principal() && next.is_principal() && prev != host {
+                if !self.interact(prev.node, next.node)? {
+                    return Ok(());
+                }
+                prev = path.pop().unwrap_or(host);
+                continue;
+            }
+            let descend = match self.kind(next.node) {
+                Kind::App => next.slot == 2,
+                kind => kind.index().is_some() && !next.is_principal(),
+            };
+            if !descend {
+                return Ok(());
+            }
+            path.push(prev);
+            prev = Port::new(next.node, 0);
+        }
+    }
+}
+
+/// The part of a context that belongs to a single index.
+#[derive(Debug, Clone, PartialEq)]
+enum Level {
+    Unknown,
+    Star,
+    Use(usize, Box<Level>),
+    Pair(Box<Level>, Box<Level>),
+}
+
+/// The path taken to reach a port during read back, as far as the fans and control nodes
+/// are concerned.
+///
+/// Passing a fan from one of its uses records which use it was at the fan's index, so that
+/// the same use can be picked when later passing a fan with that index the other way.
+/// Croissants and brackets insert and merge levels, shifting the indices above them.
+#[derive(Debug, Clone, Default)]
+struct Context {
+    levels: Vec<Level>,
+}
+
+impl Context {
+    fn level(&mut self, index: usize) -> &mut Level {
+        if self.levels.len() <= index {
+            self.levels.resize(index + 1, Level::Unknown);
+        }
+        &mut self.levels[index]
+    }
+
+    /// Update the context for moving from auxiliary port `slot` to the principal port.
+    fn enter(&mut self, kind: Kind, slot: usize) {
+        match kind {
+            Kind::Fan(i) => {
+                let level = std::mem::replace(self.level(i), Level::Unknown);
+                *self.level(i) = Level::Use(slot, Box::new(level));
+            }
+            Kind::Croissant(i) => {
+                self.level(i);
+                self.levels.insert(i, Level::Star);
+            }
+            Kind::Bracket(i) => {
+                self.level(i + 1);
+                let inner = self.levels.remove(i + 1);
+                let outer = std::mem::replace(self.level(i), Level::Unknown);
+                *self.level(i) = Level::Pair(Box::new(outer), Box::new(inner));
+            }
+            _ => unreachable!("only fans and control nodes change the context"),
+        }
+    }
+
+    /// Update the context for moving from the principal port to an auxiliary port, and return
+    /// that port, or `None` if the context doesn't say which one to take.
+    fn exit(&mut self, kind: Kind) -> Option<usize> {
+        match kind {
+            Kind::Fan(i) => match std::mem::replace(self.level(i), Level::Unknown) {
+                Level::Use(slot, level) => {
+                    *self.level(i) = *level;
+                    Some(slot)
+                }
+                _ => None,
+            },
+            Kind::Croissant(i) => match self.level(i) {
+                Level::Star => {
+                    self.levels.remove(i);
+                    Some(1)
+                }
+                _ => None,
+            },
+            Kind::Bracket(i) => match std::mem::replace(self.level(i), Level::Unknown) {
+                Level::Pair(outer, inner) => {
+                    *self.level(i) = *outer;
+                    self.levels.insert(i + 1, *inner);
+                    Some(1)
+                }
+                _ => None,
+            },
+            _ => unreachable!("only fans and control nodes change the context"),
+        }
+    }
+}
+
+/// Reads a net back into an expression, reducing it on the way.
+#[derive(Default)]
+struct Reader {
+    binders: HashMap<usize, Vec<Symbol>>,
+    next_name: usize,
+    /// The parameter each function read back was built from.
+    origins: HashMap<Symbol, Symbol>,
+}
+
+impl Reader {
+    fn read(
+        &mut self,
+        net: &mut Net,
+        host: Port,
+        context: Context,
+    ) -> Result<Expression, LambdaError> {
+        enum Task {
+            Read(Port, Context),
+            /// Finish the function at the node, once its body has been read.
+            Function(usize, Symbol),
+            Application,
+        }
+
+        let mut tasks = vec![Task::Read(host, context)];
+        let mut results = Vec::new();
+        while let Some(task) = tasks.pop() {
+            let (host, mut context) = match task {
+                Task::Read(host, context) => (host, context),
+                Task::Function(node, name) => {
+                    self.binders.get_mut(&node).unwrap().pop();
+                    let body = results.pop().unwrap();
+                    results.push(Expression::new_function(name, body));
+                    continue;
+                }
+                Task::Application => {
+                    let rhs = results.pop().unwrap();
+                    let lhs = results.pop().unwrap();
+                    results.push(Expression::new_application(lhs, rhs));
+                    continue;
+                }
+            };
+            net.whnf(host)?;
+            let next = net.enter(host);
+            let kind = net.kind(next.node);
+            match (kind, next.slot) {
+                (Kind::Lam(param), 0) => {
+                    // Skip the names of free variables, so that they aren't captured.
+                    let name = loop {
+                        self.next_name += 1;
+                        let name = Symbol::number(self.next_name);
+                        if !net.free_names.contains(&name) {
+                            break name;
+                        }
+                    };
+                    self.binders.entry(next.node).or_default().push(name);
+                    self.origins.insert(name, param);
+                    tasks.push(Task::Function(next.node, name));
+                    tasks.push(Task::Read(Port::new(next.node, 2), context));
+                }
+                (Kind::Lam(_), 1) => match self.binders.get(&next.node).and_then(|b| b.last()) {
+                    Some(name) => results.push(Expression::Variable(*name)),
+                    None => return Err(Self::unreadable("a variable escaped its function")),
+                },
+                (Kind::App, 2) => {
+                    tasks.push(Task::Application);
+                    tasks.push(Task::Read(Port::new(next.node, 1), context.clone()));
+                    tasks.push(Task::Read(Port::new(next.node, 0), context));
+                }
+                (Kind::Free(index), 0) => results.push(Expression::Variable(net.free_names[index])),
+                (Kind::Fan(_) | Kind::Croissant(_) | Kind::Bracket(_), 0) => {
+                    match context.exit(kind) {
+                        Some(slot) => tasks.push(Task::Read(Port::new(next.node, slot), context)),
+                        None => {
+                            return Err(Self::unreadable(&format!(
+                                "the path through {:?} is unknown",
+                                kind
+                            )))
+                        }
+                    }
+                }
+                (Kind::Fan(_) | Kind::Croissant(_) | Kind::Bracket(_), slot) => {
+                    context.enter(kind, slot);
+                    tasks.push(Task::Read(Port::new(next.node, 0), context));
+                }
+                (kind, slot) => {
+                    return Err(Self::unreadable(&format!(
+                        "reached port {} of {:?}",
+                        slot, kind
+                    )))
+                }
+            }
+        }
+        Ok(results.pop().unwrap())
+    }
+
+    fn unreadable(reason: &str) -> LambdaError {
+        LambdaError::RuntimeError(format!(
+            "Interaction net could not be read back, {}.",
+            reason
+        ))
+    }
+}
@@ -0,0 +1,220 @@
+//! Serialization of expressions, with the `serde` feature.
+//!
+//! Expressions are written as nested objects tagged with their type, so that the format doesn't
+//! depend on how `Expression` is laid out:
+//!
+//! ```json
+//! {"type": "variable", "name": "x"}
+//! {"type": "function", "param": "x", "body": ...}
+//! {"type": "application", "lhs": ..., "rhs": ...}
+//! ```
+//!
+//! Errors are written as `{"type": "SyntaxError", "message": "..."}`, and symbols as strings.
+//!
+//! Serializers and deserializers recurse into the nested objects, so very deep expressions
+//! exceed their nesting limits: `serde_json` only reads expressions with a `Depth` of up to 127,
+//! such as the Church numeral for 124. `FlatExpression` writes expressions as a flat list of
+//! nodes instead, which works for any depth.
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::expr::Expression;
+use crate::label::Label;
+use crate::symbol::Symbol;
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum NodeRef<'a, V: Label> {
+    Variable {
+        name: &'a V,
+    },
+    Function {
+        param: &'a V,
+        body: &'a Expression<V>,
+    },
+    Application {
+        lhs: &'a Expression<V>,
+        rhs: &'a Expression<V>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Node<V: Label> {
+    Variable {
+        name: V,
+    },
+    Function {
+        param: V,
+        body: Expression<V>,
+    },
+    Application {
+        lhs: Expression<V>,
+        rhs: Expression<V>,
+    },
+}
+
+/// # Examples
+/// ```rust
+/// use lambda::Expression;
+///
+/// let expr: Expression = "λx.x y".parse().unwrap();
+/// let json = serde_json::to_string(&expr).unwrap();
+/// assert_eq!(
+///     json,
+///     concat!(
+///         r#"{"type":"function","param":"x","body":{"type":"application","#,
+///         r#""lhs":{"type":"variable","name":"x"},"rhs":{"type":"variable","name":"y"}}}"#,
+///     )
+/// );
+/// assert_eq!(serde_json::from_str::<Expression>(&json).unwrap(), expr);
+/// ```
+impl<V: Label + Serialize> Serialize for Expression<V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let node = match self {
+            Expression::Variable(name) => NodeRef::Variable { name },
+            Expression::Function(param, body) => NodeRef::Function { param, body },
+            Expression::Application(lhs, rhs) => NodeRef::Application { lhs, rhs },
+        };
+        node.serialize(serializer)
+    }
+}
+
+impl<'de, V: Label + Deserialize<'de>> Deserialize<'de> for Expression<V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match Node::deserialize(deserializer)? {
+            Node::Variable { name } => Expression::Variable(name),
+            Node::Function { param, body } => Expression::new_function(param, body),
+            Node::Application { lhs, rhs } => Expression::new_application(lhs, rhs),
+        })
+    }
+}
+
+/// An expression serialized as a flat list of nodes in postorder, rather than as nested objects.
+///
+/// Each node refers to its subexpressions by their index in the list, and the last node is the
+/// whole expression. Neither writing nor reading the list recurses, so unlike `Expression` this
+/// works for expressions of any depth:
+///
+/// ```json
+/// [{"type": "variable", "name": "x"}, {"type": "function", "param": "x", "body": 0}]
+/// ```
+///
+/// # Examples
+/// ```rust
+/// use lambda::{Expression, FlatExpression};
+///
+/// let expr: Expression = "λx.x y".parse().unwrap();
+/// let json = serde_json::to_string(&FlatExpression(expr.clone())).unwrap();
+/// assert_eq!(
+///     json,
+///     concat!(
+///         r#"[{"type":"variable","name":"x"},{"type":"variable","name":"y"},"#,
+///         r#"{"type":"application","lhs":0,"rhs":1},{"type":"function","param":"x","body":2}]"#,
+///     )
+/// );
+/// let FlatExpression(read) = serde_json::from_str(&json).unwrap();
+/// assert_eq!(read, expr);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlatExpression<V: Label = Symbol>(pub Expression<V>);
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum FlatNode<V> {
+    Variable { name: V },
+    Function { param: V, body: usize },
+    Application { lhs: usize, rhs: usize },
+}
+
+impl<V: Label + Serialize> Serialize for FlatExpression<V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        enum Task<'a, V: Label> {
+            Visit(&'a Expression<V>),
+            Function(&'a V),
+            Application,
+        }
+
+        let mut nodes = Vec::new();
+        // The indices of the nodes written for the subexpressions visited so far.
+        let mut indices = Vec::new();
+        let mut tasks = vec![Task::Visit(&self.0)];
+        while let Some(task) = tasks.pop() {
+            let node = match task {
+                Task::Visit(Expression::Variable(name)) => FlatNode::Variable { name },
+                Task::Visit(Expression::Function(param, body)) => {
+                    tasks.push(Task::Function(param));
+                    tasks.push(Task::Visit(body));
+                    continue;
+                }
+                Task::Visit(Expression::Application(lhs, rhs)) => {
+                    tasks.push(Task::Application);
+                    tasks.push(Task::Visit(rhs));
+                    tasks.push(Task::Visit(lhs));
+                    continue;
+                }
+                Task::Function(param) => FlatNode::Function {
+                    param,
+                    body: indices.pop().unwrap(),
+                },
+                Task::Application => {
+                    let rhs = indices.pop().unwrap();
+                    let lhs = indices.pop().unwrap();
+                    FlatNode::Application { lhs, rhs }
+                }
+            };
+            indices.push(nodes.len());
+            nodes.push(node);
+        }
+        nodes.serialize(serializer)
+    }
+}
+
+impl<'de, V: Label + Deserialize<'de>> Deserialize<'de> for FlatExpression<V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let nodes = Vec::<FlatNode<V>>::deserialize(deserializer)?;
+        // The expression of each node, until it is used as part of a later one.
+        let mut exprs: Vec<Option<Expression<V>>> = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            let mut take = |index: usize| {
+                exprs
+                    .get_mut(index)
+                    .and_then(Option::take)
+                    .ok_or_else(|| D::Error::custom(format!("invalid node index {}", index)))
+            };
+            let expr = match node {
+                FlatNode::Variable { name } => Expression::Variable(name),
+                FlatNode::Function { param, body } => Expression::new_function(param, take(body)?),
+                FlatNode::Application { lhs, rhs } => {
+                    let lhs = take(lhs)?;
+                    Expression::new_application(lhs, take(rhs)?)
+                }
+            };
+            exprs.push(Some(expr));
+        }
+        let expr = exprs
+            .pop()
+            .flatten()
+            .ok_or_else(|| D::Error::custom("no nodes"))?;
+        match exprs.iter().position(Option::is_some) {
+            Some(index) => Err(D::Error::custom(format!(
+                "node {} is not part of the expression",
+                index
+            ))),
+            None => Ok(FlatExpression(expr)),
+        }
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Symbol::from)
+    }
+}
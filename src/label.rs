@@ -0,0 +1,56 @@
+use std::hash::Hash;
+
+use crate::symbol::Symbol;
+
+/// A type that can be used to name variables in an `Expression`.
+///
+/// Labels only need to be cloned, compared and hashed, so anything from interned symbols to plain
+/// integers can be used.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{Expression, Label};
+///
+/// // λ0.λ1.0, with variables labelled by integers.
+/// let expr: Expression<u32> = Expression::new_function(
+///     0u32,
+///     Expression::new_function(1u32, Expression::new_variable(0u32)),
+/// );
+/// assert_eq!(expr.normalize().to_string(), "λ1.λ2.1");
+/// assert_eq!(u32::numbered(3), Some(3));
+/// assert_eq!(u16::numbered(70_000), None);
+/// ```
+pub trait Label: Clone + Eq + Hash {
+    /// The label of the `n`th function parameter, counting from 1, when renaming parameters, or
+    /// `None` if the type has no label for `n`.
+    ///
+    /// This is how `normalize_variables` and the evaluators name the functions they produce,
+    /// skipping the numbers of free variables so that they aren't captured.
+    fn numbered(n: usize) -> Option<Self>;
+}
+
+impl Label for Symbol {
+    fn numbered(n: usize) -> Option<Self> {
+        Some(Symbol::number(n))
+    }
+}
+
+impl Label for String {
+    fn numbered(n: usize) -> Option<Self> {
+        Some(format!("{}", n))
+    }
+}
+
+macro_rules! impl_label_for_integers {
+    ($($t:ty),*) => {
+        $(
+            impl Label for $t {
+                fn numbered(n: usize) -> Option<Self> {
+                    <$t>::try_from(n).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_label_for_integers!(u16, u32, u64, usize, i32, i64);
@@ -0,0 +1,155 @@
+use std::cell::RefCell;
+use std::mem;
+use std::rc::Rc;
+
+use crate::expr::Expression;
+use crate::normalize::normalize_variables;
+use crate::readable::rename;
+use crate::value::{self, bind, evaluate_with_origins, lookup, variable, Detach, Env, Link, Slot};
+
+/// Evaluate an expression using call-by-need, and return the normalized result.
+///
+/// Arguments are not substituted into the body of a function. Instead they are bound as
+/// thunks in an environment, and a thunk is evaluated at most once, no matter how many times
+/// the bound variable is used. This avoids the duplicated work `evaluate` does for terms like
+/// `pow two (pow two two)`, where the argument is needed in many places.
+///
+/// Whenever `evaluate` finishes, the result is the same. Since an argument is only evaluated
+/// once it is needed, this also finishes on terms like `(λx.y) ((λx.x x) (λx.x x))`, where
+/// `evaluate` loops forever on an argument that is never used.
+///
+/// # Examples
+/// ```rust
+/// use lambda::church;
+///
+/// let sixteen = church::pow() * church::two() * (church::pow() * church::two() * church::two());
+/// assert_eq!(lambda::evaluate_lazy(sixteen.clone()), sixteen.evaluate());
+/// ```
+pub fn evaluate_lazy(expr: Expression) -> Expression {
+    let (result, _) = evaluate_with_origins::<Thunk>(&expr);
+    normalize_variables(result)
+}
+
+/// Evaluate an expression like `evaluate_lazy`, and name the functions of the result after the
+/// parameters they came from in the input, like `evaluate_readable`.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{evaluate_lazy_readable, Expression};
+///
+/// let expr: Expression = "(λf.λx.f x) (λy.y)".parse().unwrap();
+/// assert_eq!(evaluate_lazy_readable(expr).to_string(), "λx.x");
+/// ```
+pub fn evaluate_lazy_readable(expr: Expression) -> Expression {
+    let (result, origins) = evaluate_with_origins::<Thunk>(&expr);
+    rename(result, |name| origins.get(name).copied())
+}
+
+/// A shared reference to a value that is evaluated when first needed, and then updated in place.
+type Thunk<'a> = Rc<RefCell<ThunkState<'a>>>;
+
+enum ThunkState<'a> {
+    Delayed(&'a Expression, Env<Thunk<'a>>),
+    Evaluating,
+    Forced(Value<'a>),
+}
+
+type Value<'a> = value::Value<'a, Thunk<'a>>;
+
+impl<'a> Slot<'a> for Thunk<'a> {
+    fn from_value(value: Value<'a>) -> Self {
+        Rc::new(RefCell::new(ThunkState::Forced(value)))
+    }
+
+    fn value(&self) -> Value<'a> {
+        run(Next::Force(self.clone()))
+    }
+
+    fn eval(expr: &'a Expression, env: &Env<Self>) -> Value<'a> {
+        run(Next::Eval(expr, env.clone()))
+    }
+}
+
+impl Detach for Thunk<'_> {
+    fn detach(&mut self, links: &mut Vec<Link<Self>>) {
+        if let Some(state) = Rc::get_mut(self) {
+            match mem::replace(state.get_mut(), ThunkState::Evaluating) {
+                ThunkState::Delayed(_, env) => links.extend(env.map(Link::Env)),
+                ThunkState::Forced(mut value) => value.detach(links),
+                ThunkState::Evaluating => {}
+            }
+        }
+    }
+}
+
+/// A step of the evaluation waiting for the value being computed.
+enum Frame<'a> {
+    /// Apply the value to an argument.
+    Apply(Thunk<'a>),
+    /// Store the value in a thunk, so that it is only computed once.
+    Update(Thunk<'a>),
+}
+
+/// The next thing to do: evaluate an expression, force a thunk, or pass a value to the top frame.
+enum Next<'a> {
+    Eval(&'a Expression, Env<Thunk<'a>>),
+    Force(Thunk<'a>),
+    Return(Value<'a>),
+}
+
+fn run(next: Next) -> Value {
+    // The evaluation uses an explicit stack of frames rather than recursion, so that deeply
+    // nested expressions and long chains of thunks can be evaluated without overflowing the
+    // stack.
+    let mut frames = Vec::new();
+    let mut next = next;
+    loop {
+        let value = match next {
+            Next::Eval(Expression::Variable(label), env) => match lookup(*label, &env) {
+                Some(thunk) => {
+                    next = Next::Force(thunk);
+                    continue;
+                }
+                None => variable(*label),
+            },
+            Next::Eval(Expression::Function(param, body), env) => Value::Closure(*param, body, env),
+            Next::Eval(Expression::Application(lhs, rhs), env) => {
+                let arg = Rc::new(RefCell::new(ThunkState::Delayed(rhs, env.clone())));
+                frames.push(Frame::Apply(arg));
+                next = Next::Eval(lhs, env);
+                continue;
+            }
+            Next::Force(thunk) => {
+                let state = mem::replace(&mut *thunk.borrow_mut(), ThunkState::Evaluating);
+                match state {
+                    ThunkState::Forced(value) => {
+                        *thunk.borrow_mut() = ThunkState::Forced(value.clone());
+                        value
+                    }
+                    ThunkState::Delayed(expr, env) => {
+                        frames.push(Frame::Update(thunk));
+                        next = Next::Eval(expr, env);
+                        continue;
+                    }
+                    // Without recursive bindings a thunk can never depend on itself.
+                    ThunkState::Evaluating => unreachable!("thunk forced while being evaluated"),
+                }
+            }
+            Next::Return(value) => value,
+        };
+        next = match frames.pop() {
+            None => return value,
+            Some(Frame::Update(thunk)) => {
+                *thunk.borrow_mut() = ThunkState::Forced(value.clone());
+                Next::Return(value)
+            }
+            Some(Frame::Apply(arg)) => match value {
+                Value::Closure(param, body, env) => Next::Eval(body, bind(param, arg, &env)),
+                Value::Neutral(mut neutral) => {
+                    Rc::make_mut(&mut neutral).args.push(arg);
+                    Next::Return(Value::Neutral(neutral))
+                }
+            },
+        };
+    }
+}
@@ -1,4 +1,5 @@
 use crate::error::LambdaError;
+use crate::symbol::Symbol;
 use crate::token::Token;
 
 pub type LexResult = Result<Vec<Token>, LambdaError>;
@@ -9,7 +10,7 @@ pub fn lex(code: &str) -> LexResult {
 
     for c in code.chars() {
         match c {
-            'a'..='z' | 'A'..='Z' | '_' | '0'..='9' => {
+            'a'..='z' | 'A'..='Z' | '_' | '0'..='9' | '\'' => {
                 current_identifier.push(c);
                 continue;
             }
@@ -17,8 +18,8 @@ pub fn lex(code: &str) -> LexResult {
         }
 
         if !current_identifier.is_empty() {
-            tokens.push(Token::Variable(current_identifier));
-            current_identifier = String::new();
+            tokens.push(Token::Variable(Symbol::new(&current_identifier)));
+            current_identifier.clear();
         }
 
         let token = match c {
@@ -36,7 +37,7 @@ pub fn lex(code: &str) -> LexResult {
     }
 
     if !current_identifier.is_empty() {
-        tokens.push(Token::Variable(current_identifier));
+        tokens.push(Token::Variable(Symbol::new(&current_identifier)));
     }
 
     Ok(tokens)
@@ -58,15 +59,26 @@ mod tests {
 
     #[test]
     fn single_variable() {
-        check_lexed_correctly("x", vec![Token::Variable("x".to_string())]);
+        check_lexed_correctly("x", vec![Token::Variable(Symbol::new("x"))]);
     }
     #[test]
     fn multi_char_variables() {
         check_lexed_correctly(
             "foo bar",
             vec![
-                Token::Variable("foo".to_string()),
-                Token::Variable("bar".to_string()),
+                Token::Variable(Symbol::new("foo")),
+                Token::Variable(Symbol::new("bar")),
+            ],
+        );
+    }
+
+    #[test]
+    fn primed_variables() {
+        check_lexed_correctly(
+            "x' x''",
+            vec![
+                Token::Variable(Symbol::new("x'")),
+                Token::Variable(Symbol::new("x''")),
             ],
         );
     }
@@ -77,14 +89,14 @@ mod tests {
             "\\  x. \n\\yy . \t(u\n\r yy)",
             vec![
                 Token::Lambda,
-                Token::Variable("x".to_string()),
+                Token::Variable(Symbol::new("x")),
                 Token::Dot,
                 Token::Lambda,
-                Token::Variable("yy".to_string()),
+                Token::Variable(Symbol::new("yy")),
                 Token::Dot,
                 Token::LeftParen,
-                Token::Variable("u".to_string()),
-                Token::Variable("yy".to_string()),
+                Token::Variable(Symbol::new("u")),
+                Token::Variable(Symbol::new("yy")),
                 Token::RightParen,
             ],
         );
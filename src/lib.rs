@@ -1,16 +1,63 @@
+mod arena;
+mod cancel;
 mod codegen;
 mod constants;
+mod dot;
 mod error;
+mod eta;
 mod evaluate;
 mod expr;
+mod graph;
+mod inet;
+#[cfg(feature = "serde")]
+mod json;
+mod label;
+mod lazy;
 mod lex;
+mod metrics;
+mod nbe;
 mod normalize;
 mod parse;
+mod pretty;
+mod readable;
+mod redex;
+mod reduction;
+mod render;
+mod symbol;
+pub mod testing;
 mod token;
+mod trace;
+mod value;
+mod visit;
 
+pub use arena::{Term, TermArena, TermId};
+pub use cancel::CancellationToken;
 pub use constants::church;
+pub use dot::DotOptions;
 pub use error::LambdaError;
-pub use evaluate::{evaluate, evaluate_no_normalization, evaluate_normalized};
-pub use expr::Expression;
-pub use normalize::normalize_variables;
+pub use eta::{beta_eta_equivalent, eta_expand, eta_reduce, evaluate_beta_eta};
+pub use evaluate::{
+    evaluate, evaluate_cancellable, evaluate_checked, evaluate_limited, evaluate_no_normalization,
+    evaluate_normalized, evaluate_with_stats, Stats,
+};
+pub use expr::{Expression, Parts};
+pub use graph::{reduction_graph, ReductionGraph};
+pub use inet::{evaluate_optimal, evaluate_optimal_readable};
+#[cfg(feature = "serde")]
+pub use json::FlatExpression;
+pub use label::Label;
+pub use lazy::{evaluate_lazy, evaluate_lazy_readable};
+pub use metrics::Metrics;
+pub use nbe::{evaluate_nbe, evaluate_nbe_readable};
+pub use normalize::{alpha_equivalent, normalize_variables, try_normalize_variables};
 pub use parse::ParseResult;
+pub use pretty::PrettyOptions;
+pub use readable::{
+    evaluate_readable, evaluate_readable_limited, evaluate_readable_with_stats, readable_names,
+};
+pub use redex::{contract_at, redex_positions, subexpression, Direction, Path};
+pub use reduction::{hnf, whnf};
+pub use render::Highlight;
+pub use symbol::Symbol;
+pub use trace::{reduce_normal_order, trace, Trace};
+pub use visit::{BoundVariables, Depth, FreeVariables, Occurrences, Size, Visitor, VisitorMut};
@@ -0,0 +1,79 @@
+use std::fmt;
+
+use crate::expr::Expression;
+use crate::label::Label;
+
+/// A summary of the shape of an expression.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{church, Metrics};
+///
+/// let expr = church::succ() * church::one();
+/// assert_eq!(
+///     expr.metrics(),
+///     Metrics {
+///         size: 16,
+///         depth: 8,
+///         abstractions: 5,
+///         applications: 5,
+///         redexes: 1,
+///     }
+/// );
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Metrics {
+    /// The number of variables, abstractions and applications.
+    pub size: usize,
+    /// The number of nodes on the longest path from the root down to a variable.
+    pub depth: usize,
+    pub abstractions: usize,
+    pub applications: usize,
+    /// The number of β-redexes, i.e. applications with a function on the lhs.
+    pub redexes: usize,
+}
+
+impl Metrics {
+    /// Measure an expression, in one pass over it.
+    pub fn of<V: Label>(expr: &Expression<V>) -> Self {
+        let variable = Metrics {
+            size: 1,
+            depth: 1,
+            ..Metrics::default()
+        };
+        // Along with the metrics of each subexpression, track whether it is a function.
+        let (metrics, _) = expr.fold(
+            |_| (variable, false),
+            |_, (body, _)| {
+                let metrics = Metrics {
+                    size: body.size + 1,
+                    depth: body.depth + 1,
+                    abstractions: body.abstractions + 1,
+                    ..body
+                };
+                (metrics, true)
+            },
+            |(lhs, is_function), (rhs, _)| {
+                let metrics = Metrics {
+                    size: lhs.size + rhs.size + 1,
+                    depth: lhs.depth.max(rhs.depth) + 1,
+                    abstractions: lhs.abstractions + rhs.abstractions,
+                    applications: lhs.applications + rhs.applications + 1,
+                    redexes: lhs.redexes + rhs.redexes + usize::from(is_function),
+                };
+                (metrics, false)
+            },
+        );
+        metrics
+    }
+}
+
+impl fmt::Display for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "size {}, depth {}, {} abstractions, {} applications, {} redexes",
+            self.size, self.depth, self.abstractions, self.applications, self.redexes
+        )
+    }
+}
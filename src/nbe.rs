@@ -0,0 +1,122 @@
+use std::rc::Rc;
+
+use crate::expr::Expression;
+use crate::normalize::normalize_variables;
+use crate::readable::rename;
+use crate::value::{self, bind, evaluate_with_origins, lookup, variable, Detach, Env, Link, Slot};
+
+/// Evaluate an expression using normalization by evaluation, and return the normalized result.
+///
+/// The expression is first evaluated into a semantic domain where functions are closures over
+/// an environment, so β-reduction just binds the argument and no substitution is needed.
+/// The value is then quoted back into an expression, applying functions to fresh variables to
+/// get at their bodies.
+///
+/// Like `evaluate`, arguments are evaluated before they are passed to a function, and whenever
+/// `evaluate` finishes, the result is the same.
+///
+/// # Examples
+/// ```rust
+/// use lambda::church;
+///
+/// let three = church::add() * church::one() * church::two();
+/// assert_eq!(lambda::evaluate_nbe(three), church::three().normalize());
+/// ```
+pub fn evaluate_nbe(expr: Expression) -> Expression {
+    let (result, _) = evaluate_with_origins::<Evaluated>(&expr);
+    normalize_variables(result)
+}
+
+/// Evaluate an expression like `evaluate_nbe`, and name the functions of the result after the
+/// parameters they came from in the input, like `evaluate_readable`.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{evaluate_nbe_readable, Expression};
+///
+/// let expr: Expression = "(λf.λx.f x) (λy.y)".parse().unwrap();
+/// assert_eq!(evaluate_nbe_readable(expr).to_string(), "λx.x");
+/// ```
+pub fn evaluate_nbe_readable(expr: Expression) -> Expression {
+    let (result, origins) = evaluate_with_origins::<Evaluated>(&expr);
+    rename(result, |name| origins.get(name).copied())
+}
+
+/// A value bound to a variable or passed as an argument, which is always evaluated first.
+#[derive(Clone)]
+struct Evaluated<'a>(Value<'a>);
+
+type Value<'a> = value::Value<'a, Evaluated<'a>>;
+
+impl<'a> Slot<'a> for Evaluated<'a> {
+    fn from_value(value: Value<'a>) -> Self {
+        Evaluated(value)
+    }
+
+    fn value(&self) -> Value<'a> {
+        self.0.clone()
+    }
+
+    fn eval(expr: &'a Expression, env: &Env<Self>) -> Value<'a> {
+        eval(expr, env)
+    }
+}
+
+impl Detach for Evaluated<'_> {
+    fn detach(&mut self, links: &mut Vec<Link<Self>>) {
+        self.0.detach(links);
+    }
+}
+
+/// A step of the evaluation waiting for the value being computed.
+enum Frame<'a> {
+    /// The value is an argument: evaluate the function to apply to it.
+    Function(&'a Expression, Env<Evaluated<'a>>),
+    /// The value is a function: apply it to the argument.
+    Apply(Value<'a>),
+}
+
+/// Evaluate an expression, with an explicit stack of frames rather than recursion, so that deeply
+/// nested expressions can be evaluated without overflowing the stack.
+fn eval<'a>(expr: &'a Expression, env: &Env<Evaluated<'a>>) -> Value<'a> {
+    let mut frames = Vec::new();
+    let mut expr = expr;
+    let mut env = env.clone();
+    loop {
+        let mut value = match expr {
+            Expression::Variable(label) => match lookup(*label, &env) {
+                Some(Evaluated(value)) => value,
+                None => variable(*label),
+            },
+            Expression::Function(param, body) => Value::Closure(*param, body, env),
+            Expression::Application(lhs, rhs) => {
+                frames.push(Frame::Function(lhs, env.clone()));
+                expr = rhs;
+                continue;
+            }
+        };
+        // Pass the value down the frames until one of them needs another expression evaluated.
+        loop {
+            match frames.pop() {
+                None => return value,
+                Some(Frame::Function(lhs, lhs_env)) => {
+                    frames.push(Frame::Apply(value));
+                    expr = lhs;
+                    env = lhs_env;
+                    break;
+                }
+                Some(Frame::Apply(arg)) => match value {
+                    Value::Closure(param, body, closure_env) => {
+                        expr = body;
+                        env = bind(param, Evaluated(arg), &closure_env);
+                        break;
+                    }
+                    Value::Neutral(mut neutral) => {
+                        Rc::make_mut(&mut neutral).args.push(Evaluated(arg));
+                        value = Value::Neutral(neutral);
+                    }
+                },
+            }
+        }
+    }
+}
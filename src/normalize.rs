@@ -1,57 +1,211 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::expr::Expression;
+use crate::error::LambdaError;
+use crate::expr::{Expression, Parts};
+use crate::label::Label;
 
 /// Normalize variables in an expression to avoid name shadowing.
 ///
 /// This will replace all variables with unique numerical identifiers, so that no function
-/// shadows a variable from a containing scope.
+/// shadows a variable from a containing scope. Numbers that are the names of free variables are
+/// skipped, so that the free variables stay free.
+///
+/// # Panics
+/// If the expression has more functions than there are numbered labels of type `V`, such as
+/// over 65535 functions with `u16` labels. See `try_normalize_variables`.
 ///
 /// # Examples
 /// ```rust
-/// use lambda::normalize_variables;
-/// assert_eq!(normalize_variables("λx.x".parse().unwrap()).to_string(), "λ1.1");
+/// use lambda::{normalize_variables, Expression};
+///
+/// let expr: Expression = "λx.x".parse().unwrap();
+/// assert_eq!(normalize_variables(expr).to_string(), "λ1.1");
+/// let expr: Expression = "λx.λy.λx.x y".parse().unwrap();
 /// assert_eq!(
-///     normalize_variables("λx.λy.λx.x y".parse().unwrap()).to_string(),
+///     normalize_variables(expr).to_string(),
 ///     "λ1.λ2.λ3.3 2"
 /// );
+/// let expr: Expression = "λx.x 1".parse().unwrap();
+/// assert_eq!(normalize_variables(expr).to_string(), "λ2.2 1");
+/// ```
+pub fn normalize_variables<V: Label>(expr: Expression<V>) -> Expression<V> {
+    match try_normalize_variables(expr) {
+        Ok(expr) => expr,
+        Err(error) => panic!("{}", error),
+    }
+}
+
+/// Normalize variables in an expression like `normalize_variables`, but return an error if there
+/// aren't enough numbered labels of type `V` to name every function.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{try_normalize_variables, Expression};
+///
+/// let mut expr: Expression<u16> = Expression::new_variable(0u16);
+/// for _ in 0..70_000 {
+///     expr = Expression::new_function(0u16, expr);
+/// }
+/// assert!(try_normalize_variables(expr).is_err());
 /// ```
-pub fn normalize_variables(expr: Expression) -> Expression {
-    let mut next_name = (1..).map(|n| format!("{}", n));
-    let mut names: HashMap<String, String> = HashMap::new();
-    normalize_variables_with_bindings(expr, &mut names, &mut next_name)
+pub fn try_normalize_variables<V: Label>(
+    expr: Expression<V>,
+) -> Result<Expression<V>, LambdaError> {
+    let mut next_name = fresh_names(&expr);
+    let mut names: HashMap<V, V> = HashMap::new();
+    normalize_variables_with_bindings(expr, &mut names, &mut next_name, &mut |_, _| {})
+}
+
+/// The numbered labels `normalize_variables` names functions with, in order, leaving out the
+/// free variables of `expr`.
+pub(crate) fn fresh_names<V: Label>(expr: &Expression<V>) -> impl Iterator<Item = V> {
+    let free: HashSet<V> = expr.free_variables().into_iter().collect();
+    (1..)
+        .map_while(V::numbered)
+        .filter(move |name| !free.contains(name))
+}
+
+/// Numbered labels that name no variable of `expr`, free or bound.
+pub(crate) fn unused_names<V: Label>(expr: &Expression<V>) -> impl Iterator<Item = V> {
+    let used: HashSet<V> = expr
+        .free_variables()
+        .into_iter()
+        .chain(expr.bound_variables())
+        .collect();
+    (1..)
+        .map_while(V::numbered)
+        .filter(move |name| !used.contains(name))
+}
+
+/// Give every function in `expr` the next name from `next_name`, like `normalize_variables`,
+/// and call `renamed(name, param)` with the new name of each parameter.
+pub(crate) fn rename_functions<V: Label>(
+    expr: Expression<V>,
+    next_name: &mut dyn Iterator<Item = V>,
+    renamed: &mut dyn FnMut(&V, &V),
+) -> Result<Expression<V>, LambdaError> {
+    normalize_variables_with_bindings(expr, &mut HashMap::new(), next_name, renamed)
 }
 
-fn normalize_variables_with_bindings(
-    expr: Expression,
-    names: &mut HashMap<String, String>,
-    next_name: &mut dyn Iterator<Item = String>,
-) -> Expression {
-    match expr {
-        Expression::Application(lhs, rhs) => Expression::new_application(
-            normalize_variables_with_bindings(*lhs, names, next_name),
-            normalize_variables_with_bindings(*rhs, names, next_name),
-        ),
-        Expression::Variable(label) => {
-            if let Some(name) = names.get(&label) {
-                Expression::new_variable(name)
-            } else {
-                Expression::new_variable(&label)
+fn normalize_variables_with_bindings<V: Label>(
+    expr: Expression<V>,
+    names: &mut HashMap<V, V>,
+    next_name: &mut dyn Iterator<Item = V>,
+    renamed: &mut dyn FnMut(&V, &V),
+) -> Result<Expression<V>, LambdaError> {
+    enum Task<V: Label> {
+        Visit(Expression<V>),
+        Function(V, V, Option<V>),
+        Application,
+    }
+
+    // Use an explicit stack of tasks rather than recursion, so that deeply nested expressions
+    // can be normalized without overflowing the stack.
+    let mut tasks = vec![Task::Visit(expr)];
+    let mut results = Vec::new();
+    while let Some(task) = tasks.pop() {
+        match task {
+            Task::Visit(expr) => match expr.into_parts() {
+                Parts::Application(lhs, rhs) => {
+                    tasks.push(Task::Application);
+                    tasks.push(Task::Visit(rhs));
+                    tasks.push(Task::Visit(lhs));
+                }
+                Parts::Variable(label) => {
+                    let name = names.get(&label).cloned().unwrap_or(label);
+                    results.push(Expression::Variable(name));
+                }
+                Parts::Function(param, body) => {
+                    let new_name = next_name.next().ok_or_else(|| {
+                        LambdaError::RuntimeError(
+                            "Too many functions to number with this label type.".to_string(),
+                        )
+                    })?;
+                    renamed(&new_name, &param);
+                    let prev = names.insert(param.clone(), new_name.clone());
+                    tasks.push(Task::Function(param, new_name, prev));
+                    tasks.push(Task::Visit(body));
+                }
+            },
+            Task::Function(param, new_name, prev) => {
+                let body = results.pop().unwrap();
+                results.push(Expression::new_function(new_name, body));
+                if let Some(p) = prev {
+                    names.insert(param, p);
+                } else {
+                    names.remove(&param);
+                }
+            }
+            Task::Application => {
+                let rhs = results.pop().unwrap();
+                let lhs = results.pop().unwrap();
+                results.push(Expression::new_application(lhs, rhs));
             }
         }
-        Expression::Function(param, body) => {
-            let new_name = next_name.next().unwrap();
-            let prev = names.insert(param.clone(), new_name.clone());
-            let normalized = Expression::new_function(
-                new_name,
-                normalize_variables_with_bindings(*body, names, next_name),
-            );
-            if let Some(p) = prev {
-                names.insert(param, p);
-            } else {
-                names.remove(&param);
+    }
+    Ok(results.pop().unwrap())
+}
+
+/// Check if two expressions are equal up to renaming of bound variables.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{alpha_equivalent, Expression};
+///
+/// let lhs: Expression = "λx.λy.x (y z)".parse().unwrap();
+/// assert!(alpha_equivalent(&lhs, &"λa.λb.a (b z)".parse().unwrap()));
+/// assert!(!alpha_equivalent(&lhs, &"λa.λb.a (b c)".parse().unwrap()));
+/// assert!(!alpha_equivalent(&lhs, &"λz.λb.z (b z)".parse().unwrap()));
+/// ```
+pub fn alpha_equivalent<V: Label>(lhs: &Expression<V>, rhs: &Expression<V>) -> bool {
+    enum Task<'a, V: Label> {
+        Compare(&'a Expression<V>, &'a Expression<V>),
+        Exit(&'a V, &'a V),
+    }
+
+    // The depths of the functions binding each variable, innermost last.
+    let mut lhs_scope: HashMap<&V, Vec<usize>> = HashMap::new();
+    let mut rhs_scope: HashMap<&V, Vec<usize>> = HashMap::new();
+    let mut depth = 0;
+    let mut tasks = vec![Task::Compare(lhs, rhs)];
+    while let Some(task) = tasks.pop() {
+        match task {
+            Task::Compare(Expression::Variable(lhs), Expression::Variable(rhs)) => {
+                let lhs_binder = lhs_scope.get(lhs).and_then(|s| s.last());
+                let rhs_binder = rhs_scope.get(rhs).and_then(|s| s.last());
+                let equivalent = match (lhs_binder, rhs_binder) {
+                    // Free variables must have the same name, and bound variables the same binder.
+                    (None, None) => lhs == rhs,
+                    (lhs_binder, rhs_binder) => lhs_binder == rhs_binder,
+                };
+                if !equivalent {
+                    return false;
+                }
+            }
+            Task::Compare(
+                Expression::Function(lhs_param, lhs_body),
+                Expression::Function(rhs_param, rhs_body),
+            ) => {
+                lhs_scope.entry(lhs_param).or_default().push(depth);
+                rhs_scope.entry(rhs_param).or_default().push(depth);
+                depth += 1;
+                tasks.push(Task::Exit(lhs_param, rhs_param));
+                tasks.push(Task::Compare(lhs_body, rhs_body));
+            }
+            Task::Compare(
+                Expression::Application(lhs_lhs, lhs_rhs),
+                Expression::Application(rhs_lhs, rhs_rhs),
+            ) => {
+                tasks.push(Task::Compare(lhs_rhs, rhs_rhs));
+                tasks.push(Task::Compare(lhs_lhs, rhs_lhs));
+            }
+            Task::Compare(_, _) => return false,
+            Task::Exit(lhs_param, rhs_param) => {
+                lhs_scope.get_mut(lhs_param).unwrap().pop();
+                rhs_scope.get_mut(rhs_param).unwrap().pop();
+                depth -= 1;
             }
-            normalized
         }
     }
+    true
 }
@@ -1,7 +1,9 @@
+use std::collections::HashMap;
 use std::str;
 
 use crate::error::LambdaError;
 use crate::expr::Expression;
+use crate::symbol::Symbol;
 use crate::token::Token;
 use crate::token::TokenIterator;
 
@@ -17,61 +19,169 @@ impl str::FromStr for Expression {
 
     fn from_str(s: &str) -> ParseResult {
         let tokens = lex::lex(s)?;
-        let mut tokens = TokenIterator::new(&tokens);
-        parse_expression(&mut tokens)
+        parse_expression(&tokens)
     }
 }
 
-fn parse_expression(tokens: &mut TokenIterator) -> ParseResult {
-    let mut last_expr: Option<Expression> = None;
-    let expression_end = tokens.end_of_expr();
+/// Parse an expression with variables labelled by their names.
+///
+/// # Examples
+/// ```rust
+/// use lambda::Expression;
+///
+/// let expr: Expression<String> = "λx.x y".parse().unwrap();
+/// assert_eq!(expr, Expression::new_function("x", "x y".parse().unwrap()));
+/// ```
+impl str::FromStr for Expression<String> {
+    type Err = LambdaError;
+
+    fn from_str(s: &str) -> Result<Self, LambdaError> {
+        s.parse::<Expression<Symbol>>().map(Expression::from)
+    }
+}
+
+/// A sequence of expressions applied to each other, as found between a pair of parentheses, in
+/// the body of a function, or at the top level.
+struct Group {
+    expr: Option<Expression>,
+    /// The index just after the token that ends the group.
+    end: usize,
+    /// The parameters of the functions around the group, if it is their body.
+    params: Vec<Symbol>,
+}
 
-    while let Some(token) = tokens.next() {
-        let expr = match token {
-            Token::Dot => Err(LambdaError::SyntaxError(
-                "Unexpected dot outside of function.".to_string(),
+impl Group {
+    fn finish(self) -> ParseResult {
+        match self.expr {
+            Some(expr) => Ok(self
+                .params
+                .into_iter()
+                .rev()
+                .fold(expr, |body, param| Expression::new_function(param, body))),
+            None => Err(LambdaError::SyntaxError(
+                "The code does not contain an expression.".to_string(),
             )),
-            Token::Variable(label) => Ok(Expression::new_variable(label)),
-            Token::LeftParen => parse_expression(tokens),
-            Token::RightParen => break,
-            Token::Lambda => {
-                let param = match tokens.next() {
-                    Some(Token::Variable(label)) => label.clone(),
+        }
+    }
+}
+
+fn parse_expression(tokens: &Vec<Token>) -> ParseResult {
+    // Groups are kept on an explicit stack rather than parsed recursively, so that deeply
+    // nested code can be parsed without overflowing the stack.
+    let ends = group_ends(tokens);
+    let mut tokens = TokenIterator::new(tokens);
+    let mut groups = vec![Group {
+        expr: None,
+        end: ends.top,
+        params: Vec::new(),
+    }];
+
+    loop {
+        let index = tokens.index();
+        let expr = match tokens.next() {
+            None | Some(Token::RightParen) => None,
+            Some(Token::Dot) => {
+                return Err(LambdaError::SyntaxError(
+                    "Unexpected dot outside of function.".to_string(),
+                ))
+            }
+            Some(Token::Variable(label)) => Some(Expression::Variable(*label)),
+            Some(Token::LeftParen) => {
+                groups.push(Group {
+                    expr: None,
+                    end: ends.parentheses[&index],
+                    params: Vec::new(),
+                });
+                continue;
+            }
+            Some(Token::Lambda) => {
+                let mut params = match tokens.next() {
+                    Some(Token::Variable(label)) => vec![*label],
                     _ => {
                         return Err(LambdaError::SyntaxError(
                             "Expected variable after lambda.".to_string(),
                         ))
                     }
                 };
-                assert!(match tokens.next() {
-                    Some(Token::Dot) => true,
-                    _ => {
-                        return Err(LambdaError::SyntaxError(
-                            "Expected dot after parameter.".to_string(),
-                        ));
+                // `λx y.body` is short for `λx.λy.body`.
+                loop {
+                    match tokens.next() {
+                        Some(Token::Variable(label)) => params.push(*label),
+                        Some(Token::Dot) => break,
+                        _ => {
+                            return Err(LambdaError::SyntaxError(
+                                "Expected dot after parameter.".to_string(),
+                            ));
+                        }
                     }
-                });
+                }
 
-                let body = parse_expression(tokens)?;
-                Ok(Expression::new_function(param, body))
+                // The body extends as far as the group the function is in.
+                let end = groups.last().unwrap().end;
+                groups.push(Group {
+                    expr: None,
+                    end,
+                    params,
+                });
+                continue;
             }
         };
 
-        let y = expr?;
-        last_expr = match last_expr {
-            None => Some(y),
-            Some(x) => Some(Expression::new_application(x, y)),
-        };
-
-        if tokens.index() == expression_end {
-            break;
+        // Add the expression to the current group, finishing it and passing it on to the
+        // enclosing group if it was the last.
+        let mut expr = expr;
+        loop {
+            let group = groups.last_mut().unwrap();
+            if let Some(y) = expr {
+                group.expr = match group.expr.take() {
+                    None => Some(y),
+                    Some(x) => Some(Expression::new_application(x, y)),
+                };
+                if tokens.index() != group.end {
+                    break;
+                }
+            }
+            let finished = groups.pop().unwrap().finish()?;
+            if groups.is_empty() {
+                return Ok(finished);
+            }
+            expr = Some(finished);
         }
     }
+}
 
-    match last_expr {
-        Some(expr) => Ok(expr),
-        None => Err(LambdaError::SyntaxError(
-            "The code does not contain an expression.".to_string(),
-        )),
+/// Where the groups in some code end.
+struct GroupEnds {
+    /// The index just after the first unmatched right parenthesis, or the number of tokens.
+    top: usize,
+    /// For the index of each left parenthesis, the index just after the matching right
+    /// parenthesis, or the number of tokens if there is none.
+    parentheses: HashMap<usize, usize>,
+}
+
+fn group_ends(tokens: &[Token]) -> GroupEnds {
+    let mut ends = GroupEnds {
+        top: tokens.len(),
+        parentheses: HashMap::new(),
+    };
+    let mut open = Vec::new();
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::LeftParen => open.push(index),
+            Token::RightParen => match open.pop() {
+                Some(left) => {
+                    ends.parentheses.insert(left, index + 1);
+                }
+                None => {
+                    ends.top = index + 1;
+                    break;
+                }
+            },
+            _ => {}
+        }
+    }
+    for left in open {
+        ends.parentheses.insert(left, ends.top);
     }
+    ends
 }
@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::expr::Expression;
+use crate::label::Label;
+
+/// Options for pretty printing an expression with `Expression::pretty`.
+///
+/// The default options print on a single line, with `λ`, and with only the parentheses needed
+/// to parse the result back into the same expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrettyOptions {
+    /// Write functions as `\x.x` rather than `λx.x`.
+    pub ascii: bool,
+    /// Leave out parentheses that aren't needed, as applications associate to the left and
+    /// function bodies extend as far to the right as possible. Otherwise every function or
+    /// application that is part of an application is parenthesized, like `Display` does.
+    pub minimal_parentheses: bool,
+    /// Write nested functions with all their parameters at once, as `λx y.x`.
+    pub multi_parameter: bool,
+    /// Break expressions that don't fit within this many characters over several lines.
+    pub width: Option<usize>,
+    /// The number of spaces to indent the lines of a broken expression by.
+    pub indent: usize,
+}
+
+impl Default for PrettyOptions {
+    fn default() -> Self {
+        Self {
+            ascii: false,
+            minimal_parentheses: true,
+            multi_parameter: false,
+            width: None,
+            indent: 2,
+        }
+    }
+}
+
+/// Where an expression is written, which decides if it needs parentheses.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Position {
+    /// The whole expression, the body of a function, or the inside of parentheses.
+    Body,
+    /// The function of an application.
+    Lhs,
+    /// The argument of an application.
+    Rhs,
+}
+
+/// If `expr` needs parentheses around it when written at `position`.
+///
+/// `tail` is true if the expression is the last thing before the end of the enclosing
+/// parentheses. With `minimal` false, every function or application in an application is
+/// parenthesized, as `Display` does.
+pub(crate) fn needs_parentheses<V: Label>(
+    expr: &Expression<V>,
+    position: Position,
+    tail: bool,
+    minimal: bool,
+) -> bool {
+    match (expr, position) {
+        (Expression::Variable(_), _) | (_, Position::Body) => false,
+        _ if !minimal => true,
+        // A function extends as far to the right as possible, so it must be the last thing
+        // before the closing parenthesis.
+        (Expression::Function(_, _), _) => !tail,
+        (Expression::Application(_, _), Position::Lhs) => false,
+        (Expression::Application(_, _), Position::Rhs) => true,
+    }
+}
+
+impl<V: Label + fmt::Display> Expression<V> {
+    /// Print the expression with the given options.
+    ///
+    /// As long as the labels are valid identifiers, the result parses back into the same
+    /// expression.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::{church, Expression, PrettyOptions};
+    ///
+    /// let expr = church::add().normalize();
+    /// assert_eq!(expr.to_string(), "λ1.λ2.λ3.λ4.(1 3) ((2 3) 4)");
+    /// assert_eq!(expr.pretty(&PrettyOptions::default()), "λ1.λ2.λ3.λ4.1 3 (2 3 4)");
+    ///
+    /// let options = PrettyOptions {
+    ///     ascii: true,
+    ///     multi_parameter: true,
+    ///     ..PrettyOptions::default()
+    /// };
+    /// let code = church::add().pretty(&options);
+    /// assert_eq!(code, r"\m n f x.m f (n f x)");
+    /// assert_eq!(code.parse::<Expression>().unwrap(), church::add());
+    /// ```
+    pub fn pretty(&self, options: &PrettyOptions) -> String {
+        Printer::new(self, options).print(self)
+    }
+}
+
+/// Lays out an expression, breaking it over several lines where it doesn't fit.
+struct Printer<'a, V: Label> {
+    options: &'a PrettyOptions,
+    /// The width of each subexpression written on one line and without surrounding
+    /// parentheses, when not in tail position and in tail position.
+    widths: HashMap<*const Expression<V>, [usize; 2]>,
+    output: String,
+    column: usize,
+}
+
+enum Item<'e, V: Label> {
+    Expr {
+        expr: &'e Expression<V>,
+        position: Position,
+        /// If the expression is the last thing before the end of the enclosing parentheses, so
+        /// a function doesn't need parentheses to end it.
+        tail: bool,
+        indent: usize,
+    },
+    Text(String),
+    Break(usize),
+}
+
+impl<'a, V: Label + fmt::Display> Printer<'a, V> {
+    fn new(expr: &Expression<V>, options: &'a PrettyOptions) -> Self {
+        let mut printer = Self {
+            options,
+            widths: HashMap::new(),
+            output: String::new(),
+            column: 0,
+        };
+        if options.width.is_some() {
+            printer.measure(expr);
+        }
+        printer
+    }
+
+    fn lambda(&self) -> &'static str {
+        if self.options.ascii {
+            "\\"
+        } else {
+            "λ"
+        }
+    }
+
+    fn needs_parentheses(&self, expr: &Expression<V>, position: Position, tail: bool) -> bool {
+        needs_parentheses(expr, position, tail, self.options.minimal_parentheses)
+    }
+
+    /// The parameters written together at the start of a function, and its remaining body.
+    fn parameters<'e>(
+        &self,
+        expr: &'e Expression<V>,
+        tail: bool,
+    ) -> (Vec<&'e V>, &'e Expression<V>) {
+        let mut params = Vec::new();
+        let mut body = expr;
+        while let Expression::Function(param, next) = body {
+            params.push(param);
+            body = next;
+            let merge =
+                self.options.multi_parameter && !self.needs_parentheses(body, Position::Body, tail);
+            if !merge {
+                break;
+            }
+        }
+        (params, body)
+    }
+
+    fn width(&self, expr: &Expression<V>, tail: bool) -> usize {
+        self.widths[&(expr as *const _)][usize::from(tail)]
+    }
+
+    /// The width of an expression written on one line, including any parentheses around it.
+    fn outer_width(&self, expr: &Expression<V>, position: Position, tail: bool) -> usize {
+        if self.needs_parentheses(expr, position, tail) {
+            self.width(expr, true) + 2
+        } else {
+            self.width(expr, tail)
+        }
+    }
+
+    /// Find the width of every subexpression, from the bottom up.
+    fn measure(&mut self, expr: &Expression<V>) {
+        let mut stack = vec![(expr, false)];
+        while let Some((expr, visited)) = stack.pop() {
+            let children: Vec<&Expression<V>> = match expr {
+                Expression::Variable(_) => vec![],
+                Expression::Function(_, body) => vec![body],
+                Expression::Application(lhs, rhs) => vec![lhs, rhs],
+            };
+            if !visited && !children.is_empty() {
+                stack.push((expr, true));
+                stack.extend(children.into_iter().map(|child| (child, false)));
+                continue;
+            }
+            let widths = [false, true].map(|tail| match expr {
+                Expression::Variable(label) => label.to_string().chars().count(),
+                Expression::Function(_, _) => {
+                    let (params, body) = self.parameters(expr, tail);
+                    let params: usize = params
+                        .iter()
+                        .map(|param| param.to_string().chars().count() + 1)
+                        .sum();
+                    1 + params + self.outer_width(body, Position::Body, tail)
+                }
+                Expression::Application(lhs, rhs) => {
+                    self.outer_width(lhs, Position::Lhs, false)
+                        + 1
+                        + self.outer_width(rhs, Position::Rhs, tail)
+                }
+            });
+            self.widths.insert(expr, widths);
+        }
+    }
+
+    fn fits(&self, expr: &Expression<V>, tail: bool) -> bool {
+        match self.options.width {
+            Some(width) => self.column + self.width(expr, tail) <= width,
+            None => true,
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        self.output.push_str(text);
+        self.column += text.chars().count();
+    }
+
+    fn print(mut self, expr: &Expression<V>) -> String {
+        // Items are pushed in reverse order, so that they are written in the right order.
+        let mut stack = vec![Item::Expr {
+            expr,
+            position: Position::Body,
+            tail: true,
+            indent: 0,
+        }];
+        while let Some(item) = stack.pop() {
+            let (expr, position, tail, indent) = match item {
+                Item::Text(text) => {
+                    self.write(&text);
+                    continue;
+                }
+                Item::Break(indent) => {
+                    self.output.push('\n');
+                    self.column = 0;
+                    self.write(&" ".repeat(indent));
+                    continue;
+                }
+                Item::Expr {
+                    expr,
+                    position,
+                    tail,
+                    indent,
+                } => (expr, position, tail, indent),
+            };
+            if self.needs_parentheses(expr, position, tail) {
+                self.write("(");
+                stack.push(Item::Text(")".to_string()));
+                stack.push(Item::Expr {
+                    expr,
+                    position: Position::Body,
+                    tail: true,
+                    indent: indent + 1,
+                });
+                continue;
+            }
+            let fits = self.fits(expr, tail);
+            let step = self.options.indent;
+            match expr {
+                Expression::Variable(label) => self.write(&label.to_string()),
+                Expression::Function(_, _) => {
+                    let (params, body) = self.parameters(expr, tail);
+                    let params: Vec<String> =
+                        params.iter().map(|param| param.to_string()).collect();
+                    let header = format!("{}{}.", self.lambda(), params.join(" "));
+                    self.write(&header);
+                    let indent = if fits { indent } else { indent + step };
+                    stack.push(Item::Expr {
+                        expr: body,
+                        position: Position::Body,
+                        tail,
+                        indent,
+                    });
+                    if !fits {
+                        stack.push(Item::Break(indent));
+                    }
+                }
+                Expression::Application(lhs, rhs) if fits => {
+                    stack.push(Item::Expr {
+                        expr: rhs,
+                        position: Position::Rhs,
+                        tail,
+                        indent,
+                    });
+                    stack.push(Item::Text(" ".to_string()));
+                    stack.push(Item::Expr {
+                        expr: lhs,
+                        position: Position::Lhs,
+                        tail: false,
+                        indent,
+                    });
+                }
+                Expression::Application(_, _) => {
+                    // Write the function on the first line, and each argument on a line of its own.
+                    let mut args = Vec::new();
+                    let mut head = expr;
+                    while let Expression::Application(lhs, rhs) = head {
+                        args.push(rhs.as_ref());
+                        head = lhs;
+                        if self.needs_parentheses(head, Position::Lhs, false) {
+                            break;
+                        }
+                    }
+                    for (i, arg) in args.into_iter().enumerate() {
+                        stack.push(Item::Expr {
+                            expr: arg,
+                            position: Position::Rhs,
+                            tail: tail && i == 0,
+                            indent: indent + step,
+                        });
+                        stack.push(Item::Break(indent + step));
+                    }
+                    stack.push(Item::Expr {
+                        expr: head,
+                        position: Position::Lhs,
+                        tail: false,
+                        indent,
+                    });
+                }
+            }
+        }
+        self.output
+    }
+}
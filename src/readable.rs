@@ -0,0 +1,199 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::error::LambdaError;
+use crate::evaluate::{
+    evaluate_limited_with_origins, evaluate_with_origins, evaluate_with_stats_then, Stats,
+};
+use crate::expr::{Expression, Parts};
+use crate::normalize::{fresh_names, normalize_variables, try_normalize_variables};
+use crate::symbol::Symbol;
+use crate::visit::Visitor;
+
+/// Evaluate an expression, and name the functions of the result after the parameters they came
+/// from in the input.
+///
+/// This gives the same result as `evaluate` up to α-equivalence, but easier to read. Where the
+/// original name of a parameter would capture a variable or shadow another parameter, primes are
+/// appended to it. See `readable_names`.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{church, evaluate_readable};
+///
+/// let expr = church::succ() * church::one();
+/// assert_eq!(expr.clone().evaluate().to_string(), "λ1.λ2.1 (1 2)");
+/// assert_eq!(evaluate_readable(expr).to_string(), "λf.λx.f (f x)");
+///
+/// let expr = "(λx.λy.x) y".parse().unwrap();
+/// assert_eq!(evaluate_readable(expr).to_string(), "λy'.y");
+/// ```
+pub fn evaluate_readable(expr: Expression) -> Expression {
+    let original = original_names(&expr);
+    let (value, origins) = evaluate_with_origins(normalize_variables(expr));
+    rename(value, |param| original_name(&original, &origins, param))
+}
+
+/// Evaluate an expression like `evaluate_readable`, and also return statistics about the
+/// evaluation, like `evaluate_with_stats`.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{church, evaluate_readable_with_stats};
+///
+/// let (two, stats) = evaluate_readable_with_stats(church::succ() * church::one());
+/// assert_eq!(two.to_string(), "λf.λx.f (f x)");
+/// assert_eq!(stats.beta_steps, 3);
+/// ```
+pub fn evaluate_readable_with_stats(expr: Expression) -> (Expression, Stats) {
+    let original = original_names(&expr);
+    evaluate_with_stats_then(expr, |value, origins| {
+        rename(value, |param| original_name(&original, &origins, param))
+    })
+}
+
+/// Evaluate an expression like `evaluate_readable`, but stop with an error after `max_steps`
+/// β-reductions, like `evaluate_limited`.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{church, evaluate_readable_limited};
+///
+/// let two = church::succ() * church::one();
+/// assert_eq!(evaluate_readable_limited(two.clone(), 3).unwrap().to_string(), "λf.λx.f (f x)");
+/// assert!(evaluate_readable_limited(two, 2).is_err());
+/// ```
+pub fn evaluate_readable_limited(
+    expr: Expression,
+    max_steps: usize,
+) -> Result<Expression, LambdaError> {
+    let original = original_names(&expr);
+    let (value, origins) =
+        evaluate_limited_with_origins(try_normalize_variables(expr)?, max_steps)?;
+    Ok(rename(value, |param| {
+        original_name(&original, &origins, param)
+    }))
+}
+
+/// The parameter of `expr` that each name given by `normalize_variables` stands for.
+fn original_names(expr: &Expression) -> HashMap<Symbol, Symbol> {
+    // `normalize_variables` numbers the functions in the order `visit` reaches them.
+    struct Parameters(Vec<Symbol>);
+
+    impl Visitor<Symbol> for Parameters {
+        fn enter_function(&mut self, param: &Symbol) {
+            self.0.push(*param);
+        }
+    }
+
+    let mut params = Parameters(Vec::new());
+    expr.visit(&mut params);
+    fresh_names(expr).zip(params.0).collect()
+}
+
+/// The parameter of the input that a function of the result is named after, following renamed
+/// copies of functions back to the function they were copied from.
+fn original_name(
+    original: &HashMap<Symbol, Symbol>,
+    origins: &HashMap<Symbol, Symbol>,
+    param: &Symbol,
+) -> Option<Symbol> {
+    original.get(origins.get(param).unwrap_or(param)).copied()
+}
+
+/// Rename the functions of an expression so that no function shadows another, and no function
+/// has a numerical name.
+///
+/// Functions keep their names where possible. A name that would capture a variable or shadow
+/// another parameter gets primes appended to it, and numerical names such as those produced by
+/// `normalize_variables` are replaced with letters.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{readable_names, Expression};
+///
+/// let expr: Expression = "λ1.λ2.1 (1 2)".parse().unwrap();
+/// assert_eq!(readable_names(expr).to_string(), "λa.λb.a (a b)");
+///
+/// let expr: Expression = "λx.x (λx.x) y (λy.y)".parse().unwrap();
+/// assert_eq!(readable_names(expr).to_string(), "λx.((x (λx'.x')) y) (λy'.y')");
+/// ```
+pub fn readable_names(expr: Expression) -> Expression {
+    rename(expr, |param| {
+        let numerical = param.as_str().chars().all(|c| c.is_ascii_digit());
+        (!numerical).then_some(*param)
+    })
+}
+
+/// Rename every function, to the first name based on `preferred(param)` that is neither free in
+/// the expression nor the name of an enclosing function.
+pub(crate) fn rename(
+    expr: Expression,
+    preferred: impl Fn(&Symbol) -> Option<Symbol>,
+) -> Expression {
+    enum Task {
+        Visit(Expression),
+        Function(Symbol, Symbol, Option<Symbol>),
+        Application,
+    }
+
+    let free: HashSet<Symbol> = expr.free_variables().into_iter().collect();
+    // The new names of the functions in scope.
+    let mut taken: HashSet<Symbol> = HashSet::new();
+    let mut names: HashMap<Symbol, Symbol> = HashMap::new();
+    let mut tasks = vec![Task::Visit(expr)];
+    let mut results = Vec::new();
+    while let Some(task) = tasks.pop() {
+        match task {
+            Task::Visit(expr) => match expr.into_parts() {
+                Parts::Variable(label) => {
+                    let name = names.get(&label).copied().unwrap_or(label);
+                    results.push(Expression::Variable(name));
+                }
+                Parts::Function(param, body) => {
+                    let name = candidates(preferred(&param))
+                        .find(|name| !free.contains(name) && !taken.contains(name))
+                        .unwrap();
+                    taken.insert(name);
+                    let prev = names.insert(param, name);
+                    tasks.push(Task::Function(param, name, prev));
+                    tasks.push(Task::Visit(body));
+                }
+                Parts::Application(lhs, rhs) => {
+                    tasks.push(Task::Application);
+                    tasks.push(Task::Visit(rhs));
+                    tasks.push(Task::Visit(lhs));
+                }
+            },
+            Task::Function(param, name, prev) => {
+                let body = results.pop().unwrap();
+                results.push(Expression::new_function(name, body));
+                taken.remove(&name);
+                match prev {
+                    Some(prev) => names.insert(param, prev),
+                    None => names.remove(&param),
+                };
+            }
+            Task::Application => {
+                let rhs = results.pop().unwrap();
+                let lhs = results.pop().unwrap();
+                results.push(Expression::new_application(lhs, rhs));
+            }
+        }
+    }
+    results.pop().unwrap()
+}
+
+/// The names to try for a function: `name`, `name'`, `name''`, ..., or without a name, `a` to `z`,
+/// then `a'` to `z'`, and so on.
+fn candidates(name: Option<Symbol>) -> Box<dyn Iterator<Item = Symbol>> {
+    match name {
+        Some(name) => {
+            Box::new((0..).map(move |primes| {
+                Symbol::new(&format!("{}{}", name.as_str(), "'".repeat(primes)))
+            }))
+        }
+        None => Box::new((0..).flat_map(|primes| {
+            ('a'..='z').map(move |letter| Symbol::new(&format!("{}{}", letter, "'".repeat(primes))))
+        })),
+    }
+}
@@ -0,0 +1,167 @@
+use crate::expr::{Expression, Parts};
+use crate::label::Label;
+use crate::reduction::substitute;
+
+/// A step from an expression down to one of its parts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// From a function to its body.
+    Body,
+    /// From an application to its lhs.
+    Lhs,
+    /// From an application to its rhs.
+    Rhs,
+}
+
+/// The position of a subexpression, as the steps from the root down to it.
+///
+/// The empty path is the position of the whole expression.
+pub type Path = Vec<Direction>;
+
+/// If the expression is a β-redex, i.e. an application with a function on the lhs.
+pub(crate) fn is_redex<V: Label>(expr: &Expression<V>) -> bool {
+    matches!(expr, Expression::Application(lhs, _) if matches!(**lhs, Expression::Function(_, _)))
+}
+
+/// The position of the redex that normal order reduction contracts next: the leftmost of the
+/// outermost redexes.
+pub(crate) fn leftmost_outermost<V: Label>(expr: &Expression<V>) -> Option<Path> {
+    find_redexes(expr, false).pop()
+}
+
+/// The positions of all the β-redexes in the expression, from left to right, with each redex
+/// before the redexes inside it.
+///
+/// The first is the redex that normal order reduction contracts.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{redex_positions, Direction, Expression};
+///
+/// let expr: Expression = "λf.(λx.x) ((λy.y) f)".parse().unwrap();
+/// assert_eq!(
+///     redex_positions(&expr),
+///     [vec![Direction::Body], vec![Direction::Body, Direction::Rhs]]
+/// );
+/// ```
+pub fn redex_positions<V: Label>(expr: &Expression<V>) -> Vec<Path> {
+    find_redexes(expr, true)
+}
+
+/// The positions of the redexes in the expression in preorder, stopping at the first unless
+/// `all` is set.
+fn find_redexes<V: Label>(expr: &Expression<V>, all: bool) -> Vec<Path> {
+    // Each subexpression is pushed along with the number of steps to it, and the path to the
+    // current subexpression is kept in `path`.
+    let mut found = Vec::new();
+    let mut path = Vec::new();
+    let mut tasks = vec![(expr, None)];
+    while let Some((expr, step)) = tasks.pop() {
+        if let Some((depth, direction)) = step {
+            path.truncate(depth);
+            path.push(direction);
+        }
+        if is_redex(expr) {
+            found.push(path.clone());
+            if !all {
+                break;
+            }
+        }
+        let depth = path.len();
+        match expr {
+            Expression::Variable(_) => {}
+            Expression::Function(_, body) => tasks.push((body, Some((depth, Direction::Body)))),
+            Expression::Application(lhs, rhs) => {
+                tasks.push((rhs, Some((depth, Direction::Rhs))));
+                tasks.push((lhs, Some((depth, Direction::Lhs))));
+            }
+        }
+    }
+    found
+}
+
+/// The subexpression at `path`, if there is one.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{subexpression, Direction, Expression};
+///
+/// let expr: Expression = "λf.f x".parse().unwrap();
+/// let path = [Direction::Body, Direction::Rhs];
+/// assert_eq!(subexpression(&expr, &path), Some(&"x".parse().unwrap()));
+/// assert_eq!(subexpression(&expr, &[Direction::Lhs]), None);
+/// ```
+pub fn subexpression<'a, V: Label>(
+    expr: &'a Expression<V>,
+    path: &[Direction],
+) -> Option<&'a Expression<V>> {
+    path.iter()
+        .try_fold(expr, |expr, direction| match (expr, direction) {
+            (Expression::Function(_, body), Direction::Body) => Some(body.as_ref()),
+            (Expression::Application(lhs, _), Direction::Lhs) => Some(lhs.as_ref()),
+            (Expression::Application(_, rhs), Direction::Rhs) => Some(rhs.as_ref()),
+            _ => None,
+        })
+}
+
+/// Contract the β-redex at `path`, leaving the rest of the expression as it is.
+///
+/// Returns `None` if there is no redex at `path`. Functions are only renamed where needed to
+/// avoid capturing a variable, as in `trace`.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{contract_at, redex_positions, Direction, Expression};
+///
+/// let expr: Expression = "(λx.λy.x) ((λz.z) a)".parse().unwrap();
+/// let inner = redex_positions(&expr).pop().unwrap();
+/// let reduced = contract_at(expr.clone(), &inner).unwrap();
+/// assert_eq!(reduced.to_string(), "(λx.λy.x) a");
+/// assert_eq!(contract_at(reduced, &[]).unwrap().to_string(), "λy.a");
+/// assert_eq!(contract_at(expr, &[Direction::Lhs]), None);
+/// ```
+pub fn contract_at<V: Label>(expr: Expression<V>, path: &[Direction]) -> Option<Expression<V>> {
+    enum Frame<V: Label> {
+        Function(V),
+        Lhs(Expression<V>),
+        Rhs(Expression<V>),
+    }
+
+    if !subexpression(&expr, path).is_some_and(is_redex) {
+        return None;
+    }
+    let mut frames = Vec::new();
+    let mut expr = expr;
+    for direction in path {
+        expr = match (expr.into_parts(), direction) {
+            (Parts::Function(param, body), Direction::Body) => {
+                frames.push(Frame::Function(param));
+                body
+            }
+            (Parts::Application(lhs, rhs), Direction::Lhs) => {
+                frames.push(Frame::Lhs(rhs));
+                lhs
+            }
+            (Parts::Application(lhs, rhs), Direction::Rhs) => {
+                frames.push(Frame::Rhs(lhs));
+                rhs
+            }
+            _ => unreachable!("the path was checked"),
+        };
+    }
+    let Parts::Application(lhs, arg) = expr.into_parts() else {
+        unreachable!("the path leads to a redex")
+    };
+    let Parts::Function(param, body) = lhs.into_parts() else {
+        unreachable!("the path leads to a redex")
+    };
+    let mut expr = substitute(body, &param, arg);
+    while let Some(frame) = frames.pop() {
+        expr = match frame {
+            Frame::Function(param) => Expression::new_function(param, expr),
+            Frame::Lhs(rhs) => Expression::new_application(expr, rhs),
+            Frame::Rhs(lhs) => Expression::new_application(lhs, expr),
+        };
+    }
+    Some(expr)
+}
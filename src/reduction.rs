@@ -0,0 +1,156 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::expr::{Expression, Parts};
+use crate::label::Label;
+
+/// Reduce an expression to weak head normal form.
+///
+/// Only the head of the expression is reduced, in normal order, until it is either a function or
+/// a variable applied to any number of arguments. Neither the body of the function nor the
+/// arguments are touched, so this terminates for terms like infinite streams whose full normal
+/// form doesn't exist.
+///
+/// Unlike `evaluate`, the result is not normalized. Substitution avoids capturing variables by
+/// renaming functions where needed, with the same numerical names `normalize_variables` uses.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{whnf, Expression};
+///
+/// let expr: Expression = "(λx.λy.y x) ((λx.x x) (λx.x x))".parse().unwrap();
+/// assert_eq!(whnf(expr).to_string(), "λy.y ((λx.x x) (λx.x x))");
+///
+/// let expr: Expression = "λx.(λy.y) x".parse().unwrap();
+/// assert_eq!(whnf(expr).to_string(), "λx.(λy.y) x");
+/// ```
+pub fn whnf<V: Label>(expr: Expression<V>) -> Expression<V> {
+    // The arguments of the head, with the first argument last.
+    let mut args = Vec::new();
+    let mut head = expr;
+    loop {
+        head = match head.into_parts() {
+            Parts::Application(lhs, rhs) => {
+                args.push(rhs);
+                lhs
+            }
+            Parts::Function(param, body) => match args.pop() {
+                Some(arg) => substitute(body, &param, arg),
+                None => {
+                    head = Expression::new_function(param, body);
+                    break;
+                }
+            },
+            Parts::Variable(label) => {
+                head = Expression::Variable(label);
+                break;
+            }
+        };
+    }
+    while let Some(arg) = args.pop() {
+        head = Expression::new_application(head, arg);
+    }
+    head
+}
+
+/// Reduce an expression to head normal form.
+///
+/// This is like `whnf`, but also reduces under functions, so the result is a number of
+/// functions around a variable applied to any number of (unreduced) arguments.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{hnf, Expression};
+///
+/// let expr: Expression = "λx.(λy.y) x ((λz.z) x)".parse().unwrap();
+/// assert_eq!(hnf(expr).to_string(), "λx.x ((λz.z) x)");
+/// ```
+pub fn hnf<V: Label>(expr: Expression<V>) -> Expression<V> {
+    let mut params = Vec::new();
+    let mut expr = whnf(expr);
+    while let Expression::Function(_, _) = expr {
+        let Parts::Function(param, body) = expr.into_parts() else {
+            unreachable!()
+        };
+        params.push(param);
+        expr = whnf(body);
+    }
+    while let Some(param) = params.pop() {
+        expr = Expression::new_function(param, expr);
+    }
+    expr
+}
+
+/// Substitute `arg` for the free occurrences of `param` in `body`.
+///
+/// Functions in `body` whose parameter is free in `arg` are renamed, so that no variable of
+/// `arg` is captured.
+pub(crate) fn substitute<V: Label>(
+    body: Expression<V>,
+    param: &V,
+    arg: Expression<V>,
+) -> Expression<V> {
+    enum Task<V: Label> {
+        Visit(Expression<V>),
+        Function(V, V, Option<Expression<V>>),
+        Application,
+    }
+
+    let arg_free: HashSet<V> = arg.free_variables().into_iter().collect();
+    let mut used: HashSet<V> = arg_free.clone();
+    used.extend(body.free_variables());
+    used.extend(body.bound_variables());
+    let mut fresh_names = (1..).map_while(V::numbered);
+
+    // What each variable should be replaced with: the argument for `param`, and a new name for
+    // the parameter of each renamed function.
+    let mut replacements = HashMap::from([(param.clone(), arg)]);
+    let mut tasks = vec![Task::Visit(body)];
+    let mut results = Vec::new();
+    while let Some(task) = tasks.pop() {
+        match task {
+            Task::Visit(expr) => match expr.into_parts() {
+                Parts::Variable(label) => results.push(match replacements.get(&label) {
+                    Some(replacement) => replacement.clone(),
+                    None => Expression::Variable(label),
+                }),
+                Parts::Function(old, body) => {
+                    let new = if arg_free.contains(&old) {
+                        let name = fresh_names
+                            .find(|name| !used.contains(name))
+                            .expect("every numbered label is already in use");
+                        used.insert(name.clone());
+                        name
+                    } else {
+                        old.clone()
+                    };
+                    let prev = if new == old {
+                        replacements.remove(&old)
+                    } else {
+                        replacements.insert(old.clone(), Expression::Variable(new.clone()))
+                    };
+                    tasks.push(Task::Function(new, old, prev));
+                    tasks.push(Task::Visit(body));
+                }
+                Parts::Application(lhs, rhs) => {
+                    tasks.push(Task::Application);
+                    tasks.push(Task::Visit(rhs));
+                    tasks.push(Task::Visit(lhs));
+                }
+            },
+            Task::Function(new, old, prev) => {
+                let body = results.pop().unwrap();
+                results.push(Expression::new_function(new, body));
+                match prev {
+                    Some(prev) => replacements.insert(old, prev),
+                    None => replacements.remove(&old),
+                };
+            }
+            Task::Application => {
+                let rhs = results.pop().unwrap();
+                let lhs = results.pop().unwrap();
+                results.push(Expression::new_application(lhs, rhs));
+            }
+        }
+    }
+    results.pop().unwrap()
+}
@@ -0,0 +1,420 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::expr::Expression;
+use crate::label::Label;
+use crate::pretty::{needs_parentheses, Position};
+use crate::redex::{subexpression, Path};
+use crate::trace::Trace;
+
+/// How `Trace::to_text` marks the redexes of a reduction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    /// Don't mark anything.
+    None,
+    /// Color the redex contracted in each term red, and what the previous redex was contracted
+    /// to green, with ANSI escape codes.
+    Color,
+    /// Underline the redex contracted in each term with `^`, and what the previous redex was
+    /// contracted to with `~`, on lines of their own.
+    Markers,
+}
+
+/// A markup language to render expressions in.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Latex,
+    Html,
+    /// Plain text, as written by `Display`.
+    Text(Highlight),
+}
+
+/// A subexpression to mark.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mark {
+    /// The redex contracted in a step.
+    Redex,
+    /// The result of contracting the redex in the previous step.
+    Contractum,
+}
+
+impl<V: Label + fmt::Display> Expression<V> {
+    /// Render the expression as LaTeX math, such as `\lambda x.\,x\;y`.
+    ///
+    /// Only the parentheses needed to read the expression are written. Variables with names
+    /// longer than one character are set in `\mathit`, and characters LaTeX treats specially
+    /// are escaped. Names with `^` or `~` need the `amsmath` package for `\text`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::Expression;
+    ///
+    /// let expr: Expression = "(λx.x) (f y_1)".parse().unwrap();
+    /// assert_eq!(expr.to_latex(), r"(\lambda x.\,x)\;(f\;\mathit{y\_1})");
+    /// ```
+    pub fn to_latex(&self) -> String {
+        render(self, Format::Latex, &[]).0
+    }
+
+    /// Render the expression as HTML, with a `span` around each part of it.
+    ///
+    /// The spans have the classes `lambda-variable`, `lambda-function`, `lambda-parameter` and
+    /// `lambda-application`, so they can be styled with CSS.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::Expression;
+    ///
+    /// let expr: Expression = "λx.x y".parse().unwrap();
+    /// assert_eq!(
+    ///     expr.to_html(),
+    ///     concat!(
+    ///         r#"<span class="lambda-function">λ<span class="lambda-parameter">x</span>."#,
+    ///         r#"<span class="lambda-application"><span class="lambda-variable">x</span> "#,
+    ///         r#"<span class="lambda-variable">y</span></span></span>"#,
+    ///     )
+    /// );
+    /// ```
+    pub fn to_html(&self) -> String {
+        render(self, Format::Html, &[]).0
+    }
+}
+
+impl<V: Label + fmt::Display> Trace<V> {
+    /// Render the trace as text, with one term per line, as written by `Display`.
+    ///
+    /// Each step marks the redex contracted in it, and what the previous redex was contracted
+    /// to, as chosen by `highlight`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::{trace, Expression, Highlight};
+    ///
+    /// let expr: Expression = "(λx.x) ((λy.y) z)".parse().unwrap();
+    /// assert_eq!(
+    ///     trace(expr, 10).to_text(Highlight::Markers),
+    ///     concat!(
+    ///         "  (λx.x) ((λy.y) z)\n",
+    ///         "  ^^^^^^^^^^^^^^^^^\n",
+    ///         "→ (λy.y) z\n",
+    ///         "  ~~~~~~~~\n",
+    ///         "  ^^^^^^^^\n",
+    ///         "→ z\n",
+    ///         "  ~\n",
+    ///     )
+    /// );
+    /// ```
+    pub fn to_text(&self, highlight: Highlight) -> String {
+        let mut text = String::new();
+        for (i, (term, redex, contractum)) in self.contractions().enumerate() {
+            let marks = marks(term, redex, contractum);
+            let (line, spans) = render(term, Format::Text(highlight), &marks);
+            text.push_str(if i == 0 { "  " } else { "→ " });
+            text.push_str(&line);
+            text.push('\n');
+            if highlight == Highlight::Markers {
+                for (mark, marker) in [(Mark::Contractum, '~'), (Mark::Redex, '^')] {
+                    if let Some((span, _)) = spans.iter().find(|(_, m)| *m == mark) {
+                        let underline =
+                            " ".repeat(span.start) + &marker.to_string().repeat(span.len());
+                        text.push_str(&format!("  {}\n", underline));
+                    }
+                }
+            }
+        }
+        if !self.is_complete() {
+            text.push_str("→ …\n");
+        }
+        text
+    }
+
+    /// Render the trace as a LaTeX `align*` environment, with one term per line and the redex
+    /// contracted in each term underlined.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::{trace, Expression};
+    ///
+    /// let expr: Expression = "(λx.x) y".parse().unwrap();
+    /// assert_eq!(
+    ///     trace(expr, 10).to_latex(),
+    ///     "\\begin{align*}\n  & \\underline{(\\lambda x.\\,x)\\;y} \\\\\n  \\to_\\beta\\; & y\n\\end{align*}\n"
+    /// );
+    /// ```
+    pub fn to_latex(&self) -> String {
+        let lines: Vec<String> = self
+            .contractions()
+            .enumerate()
+            .map(|(i, (term, redex, _))| {
+                let arrow = if i == 0 { "" } else { "\\to_\\beta\\; " };
+                let (term, _) = render(term, Format::Latex, &marks(term, redex, None));
+                format!("  {}& {}", arrow, term)
+            })
+            .chain((!self.is_complete()).then(|| "  \\to_\\beta\\; & \\cdots".to_string()))
+            .collect();
+        format!(
+            "\\begin{{align*}}\n{}\n\\end{{align*}}\n",
+            lines.join(" \\\\\n")
+        )
+    }
+
+    /// Render the trace as an HTML ordered list, with one term per item and the redex contracted
+    /// in each term marked with the class `lambda-redex`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::{trace, Expression};
+    ///
+    /// let expr: Expression = "(λx.x) y".parse().unwrap();
+    /// let html = trace(expr, 10).to_html();
+    /// assert!(html.starts_with(r#"<ol class="lambda-trace">"#));
+    /// assert!(html.contains(r#"<li><span class="lambda-application lambda-redex">"#));
+    /// assert!(html.contains(r#"<li><span class="lambda-variable">y</span></li>"#));
+    /// ```
+    pub fn to_html(&self) -> String {
+        let mut html = String::from("<ol class=\"lambda-trace\">\n");
+        for (term, redex, _) in self.contractions() {
+            let (term, _) = render(term, Format::Html, &marks(term, redex, None));
+            html.push_str(&format!("<li>{}</li>\n", term));
+        }
+        if !self.is_complete() {
+            html.push_str("<li class=\"lambda-incomplete\">…</li>\n");
+        }
+        html.push_str("</ol>\n");
+        html
+    }
+}
+
+/// The subexpressions of `term` at `redex` and `contractum`.
+fn marks<'a, V: Label>(
+    term: &'a Expression<V>,
+    redex: Option<&Path>,
+    contractum: Option<&Path>,
+) -> Vec<(&'a Expression<V>, Mark)> {
+    // The redex goes last, so that it is shown over a contractum in the same place.
+    [(contractum, Mark::Contractum), (redex, Mark::Redex)]
+        .into_iter()
+        .filter_map(|(path, mark)| Some((subexpression(term, path?)?, mark)))
+        .collect()
+}
+
+/// Render `expr`, marking the given subexpressions.
+///
+/// Also returns where each marked subexpression ended up in the text, in characters, which is
+/// only meaningful for `Format::Text`.
+fn render<V: Label + fmt::Display>(
+    expr: &Expression<V>,
+    format: Format,
+    marks: &[(&Expression<V>, Mark)],
+) -> (String, Vec<(Range<usize>, Mark)>) {
+    enum Item<'a, V: Label> {
+        Expr {
+            expr: &'a Expression<V>,
+            position: Position,
+            /// If the expression is the last thing before the end of the enclosing parentheses.
+            tail: bool,
+        },
+        Text(&'static str),
+        /// The end of an expression with the given marks.
+        Close(Vec<Mark>),
+    }
+
+    let mut output = Output::default();
+    let mut spans = Vec::new();
+    // The marks of the expressions being written, with where they start, innermost last.
+    let mut open: Vec<(Mark, usize)> = Vec::new();
+    let mut items = vec![Item::Expr {
+        expr,
+        position: Position::Body,
+        tail: true,
+    }];
+    while let Some(item) = items.pop() {
+        let (expr, position, tail) = match item {
+            Item::Text(text) => {
+                output.push(text);
+                continue;
+            }
+            Item::Close(marks) => {
+                match format {
+                    Format::Latex => marks.iter().for_each(|_| output.push_markup("}")),
+                    Format::Html => output.push_markup("</span>"),
+                    Format::Text(_) => {}
+                }
+                for _ in &marks {
+                    let (mark, start) = open.pop().unwrap();
+                    spans.push((start..output.column, mark));
+                }
+                if format == Format::Text(Highlight::Color) && !marks.is_empty() {
+                    output.push_markup(RESET);
+                    for (mark, _) in &open {
+                        output.push_markup(color(*mark));
+                    }
+                }
+                continue;
+            }
+            Item::Expr {
+                expr,
+                position,
+                tail,
+            } => (expr, position, tail),
+        };
+        // Text is written like `Display`, with all the parentheses.
+        let minimal = !matches!(format, Format::Text(_));
+        if needs_parentheses(expr, position, tail, minimal) {
+            output.push("(");
+            items.push(Item::Text(")"));
+            items.push(Item::Expr {
+                expr,
+                position: Position::Body,
+                tail: true,
+            });
+            continue;
+        }
+
+        let marks: Vec<Mark> = marks
+            .iter()
+            .filter(|(marked, _)| std::ptr::eq(*marked, expr))
+            .map(|(_, mark)| *mark)
+            .collect();
+        match format {
+            Format::Latex => {
+                for mark in &marks {
+                    output.push_markup(match mark {
+                        Mark::Redex => "\\underline{",
+                        Mark::Contractum => "\\overline{",
+                    });
+                }
+            }
+            Format::Html => {
+                let class = match expr {
+                    Expression::Variable(_) => "lambda-variable",
+                    Expression::Function(_, _) => "lambda-function",
+                    Expression::Application(_, _) => "lambda-application",
+                };
+                let marks: String = marks
+                    .iter()
+                    .map(|mark| match mark {
+                        Mark::Redex => " lambda-redex",
+                        Mark::Contractum => " lambda-contractum",
+                    })
+                    .collect();
+                output.push_markup(&format!("<span class=\"{}{}\">", class, marks));
+            }
+            Format::Text(highlight) => {
+                if highlight == Highlight::Color {
+                    marks
+                        .iter()
+                        .for_each(|mark| output.push_markup(color(*mark)));
+                }
+            }
+        }
+        open.extend(marks.iter().map(|mark| (*mark, output.column)));
+        items.push(Item::Close(marks));
+        match expr {
+            Expression::Variable(label) => output.push(&label_text(label, format)),
+            Expression::Function(param, body) => {
+                let header = match format {
+                    Format::Latex => format!("\\lambda {}.\\,", label_text(param, format)),
+                    Format::Html => format!(
+                        "λ<span class=\"lambda-parameter\">{}</span>.",
+                        label_text(param, format)
+                    ),
+                    Format::Text(_) => format!("λ{}.", label_text(param, format)),
+                };
+                output.push(&header);
+                items.push(Item::Expr {
+                    expr: body,
+                    position: Position::Body,
+                    tail,
+                });
+            }
+            Expression::Application(lhs, rhs) => {
+                let separator = match format {
+                    Format::Latex => "\\;",
+                    Format::Html | Format::Text(_) => " ",
+                };
+                items.push(Item::Expr {
+                    expr: rhs,
+                    position: Position::Rhs,
+                    tail,
+                });
+                items.push(Item::Text(separator));
+                items.push(Item::Expr {
+                    expr: lhs,
+                    position: Position::Lhs,
+                    tail: false,
+                });
+            }
+        }
+    }
+    (output.text, spans)
+}
+
+const RESET: &str = "\x1b[0m";
+
+/// The ANSI escape code to color a mark with.
+fn color(mark: Mark) -> &'static str {
+    match mark {
+        Mark::Redex => "\x1b[1;31m",
+        Mark::Contractum => "\x1b[32m",
+    }
+}
+
+/// Rendered text, along with the number of characters written that are shown as text.
+#[derive(Default)]
+struct Output {
+    text: String,
+    column: usize,
+}
+
+impl Output {
+    fn push(&mut self, text: &str) {
+        self.text.push_str(text);
+        self.column += text.chars().count();
+    }
+
+    /// Write markup, which doesn't count towards the column.
+    fn push_markup(&mut self, markup: &str) {
+        self.text.push_str(markup);
+    }
+}
+
+/// A label, escaped for the format.
+fn label_text<V: fmt::Display>(label: &V, format: Format) -> String {
+    let label = label.to_string();
+    match format {
+        Format::Latex => {
+            let escaped = escape_latex(&label);
+            // Primes don't count towards the length, so `x'` is set like `x`.
+            if label.trim_end_matches('\'').chars().count() > 1 {
+                format!("\\mathit{{{}}}", escaped)
+            } else {
+                escaped
+            }
+        }
+        Format::Text(_) => label,
+        Format::Html => label
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;"),
+    }
+}
+
+/// Escape the characters LaTeX treats specially, so that they can be written in math mode.
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\backslash{}"),
+            '^' => escaped.push_str("\\text{\\textasciicircum}"),
+            '~' => escaped.push_str("\\text{\\textasciitilde}"),
+            '{' | '}' | '&' | '%' | '#' | '$' | '_' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
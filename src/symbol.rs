@@ -0,0 +1,175 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{OnceLock, RwLock};
+
+/// An interned variable name.
+///
+/// Each distinct name is stored once in a global symbol table, and a `Symbol` is just its index
+/// in that table. Symbols are therefore cheap to copy, and comparing two of them is an integer
+/// comparison. Interned names live for the rest of the program.
+///
+/// # Examples
+/// ```rust
+/// use lambda::Symbol;
+///
+/// let x = Symbol::new("x");
+/// assert_eq!(x, Symbol::new("x"));
+/// assert_ne!(x, Symbol::new("y"));
+/// assert_eq!(x.as_str(), "x");
+/// assert_eq!(Symbol::get("x"), Some(x));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol(u32);
+
+#[derive(Default)]
+struct SymbolTable {
+    names: Vec<&'static str>,
+    ids: HashMap<&'static str, Symbol>,
+    /// The symbols for the names "1", "2", ... that have been interned, in order.
+    numbers: Vec<Symbol>,
+}
+
+fn table() -> &'static RwLock<SymbolTable> {
+    static TABLE: OnceLock<RwLock<SymbolTable>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = SymbolTable::default();
+        table.intern("");
+        RwLock::new(table)
+    })
+}
+
+impl SymbolTable {
+    fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(symbol) = self.ids.get(name) {
+            return *symbol;
+        }
+        let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+        let symbol = Symbol(self.names.len() as u32);
+        self.names.push(name);
+        self.ids.insert(name, symbol);
+        symbol
+    }
+}
+
+impl Symbol {
+    /// Intern a name, returning the symbol for it.
+    pub fn new(name: &str) -> Self {
+        if let Some(symbol) = Self::get(name) {
+            return symbol;
+        }
+        table().write().unwrap().intern(name)
+    }
+
+    /// The symbol for the name `n`, as used for normalized variable names.
+    ///
+    /// Names are only formatted the first time they are needed, so normalizing an expression
+    /// doesn't allocate a new string per function.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::Symbol;
+    /// assert_eq!(Symbol::number(12), Symbol::new("12"));
+    /// ```
+    pub fn number(n: usize) -> Self {
+        assert!(n > 0, "numbered names start at 1");
+        if let Some(symbol) = table().read().unwrap().numbers.get(n - 1) {
+            return *symbol;
+        }
+        let mut table = table().write().unwrap();
+        while table.numbers.len() < n {
+            let name = format!("{}", table.numbers.len() + 1);
+            let symbol = table.intern(&name);
+            table.numbers.push(symbol);
+        }
+        table.numbers[n - 1]
+    }
+
+    /// Find the symbol for a name, if it has been interned.
+    pub fn get(name: &str) -> Option<Self> {
+        table().read().unwrap().ids.get(name).copied()
+    }
+
+    /// The name this symbol was interned from.
+    pub fn as_str(self) -> &'static str {
+        table().read().unwrap().names[self.0 as usize]
+    }
+
+    /// The index of this symbol in the symbol table.
+    ///
+    /// Indices are given out in the order names are interned.
+    pub fn index(self) -> u32 {
+        self.0
+    }
+
+    /// The number of names in the symbol table.
+    pub fn count() -> usize {
+        table().read().unwrap().names.len()
+    }
+}
+
+/// The empty name, which is always the first symbol in the table.
+impl Default for Symbol {
+    fn default() -> Self {
+        Symbol(0)
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Self {
+        Symbol::new(name)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Self {
+        Symbol::new(&name)
+    }
+}
+
+impl From<&String> for Symbol {
+    fn from(name: &String) -> Self {
+        Symbol::new(name)
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+/// Symbols are ordered by name, not by when they were interned.
+impl Ord for Symbol {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self == other {
+            Ordering::Equal
+        } else {
+            self.as_str().cmp(other.as_str())
+        }
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
@@ -0,0 +1,140 @@
+//! Helpers for testing code that works with lambda terms.
+
+use crate::expr::Expression;
+use crate::symbol::Symbol;
+
+/// A generator of random expressions, for property tests.
+///
+/// Each expression has between 1 and `max_size` nodes (see `Expression::size`). Both parameters
+/// and variables are drawn from a pool of names, so the expressions freely mix bound and free
+/// variables, shadowing, and functions on either side of applications. The same seed always
+/// produces the same expressions.
+///
+/// # Examples
+/// ```rust
+/// use lambda::testing::TermGenerator;
+/// use lambda::Expression;
+///
+/// for expr in TermGenerator::new(42).with_max_size(30).take(100) {
+///     assert!(expr.size() <= 30);
+///     assert_eq!(expr.to_string().parse::<Expression>().unwrap(), expr);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TermGenerator {
+    max_size: usize,
+    variables: Vec<Symbol>,
+    rng: Rng,
+}
+
+impl TermGenerator {
+    /// A generator of expressions with up to 20 nodes, with variables named `x`, `y`, `z`, `f`
+    /// and `g`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            max_size: 20,
+            variables: ["x", "y", "z", "f", "g"].map(Symbol::new).to_vec(),
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// Generate expressions with at most `max_size` nodes.
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        assert!(max_size > 0, "expressions have at least one node");
+        self.max_size = max_size;
+        self
+    }
+
+    /// Name parameters and variables from `variables`.
+    ///
+    /// For the expressions to round trip through `Display`, the names need to be valid
+    /// identifiers.
+    pub fn with_variables<I>(mut self, variables: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Symbol>,
+    {
+        self.variables = variables.into_iter().map(Into::into).collect();
+        assert!(!self.variables.is_empty(), "the variable pool is empty");
+        self
+    }
+
+    /// Generate an expression with exactly `size` nodes.
+    pub fn generate_sized(&mut self, size: usize) -> Expression {
+        enum Task {
+            Generate(usize),
+            Function(Symbol),
+            Application,
+        }
+
+        let mut tasks = vec![Task::Generate(size.max(1))];
+        let mut results = Vec::new();
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Generate(1) => results.push(Expression::Variable(self.variable())),
+                Task::Generate(size) if size == 2 || self.rng.below(2) == 0 => {
+                    let param = self.variable();
+                    tasks.push(Task::Function(param));
+                    tasks.push(Task::Generate(size - 1));
+                }
+                Task::Generate(size) => {
+                    let lhs = 1 + self.rng.below(size - 2);
+                    tasks.push(Task::Application);
+                    tasks.push(Task::Generate(size - 1 - lhs));
+                    tasks.push(Task::Generate(lhs));
+                }
+                Task::Function(param) => {
+                    let body = results.pop().unwrap();
+                    results.push(Expression::new_function(param, body));
+                }
+                Task::Application => {
+                    let rhs = results.pop().unwrap();
+                    let lhs = results.pop().unwrap();
+                    results.push(Expression::new_application(lhs, rhs));
+                }
+            }
+        }
+        results.pop().unwrap()
+    }
+
+    /// Generate an expression with a random size of at most `max_size` nodes.
+    pub fn generate(&mut self) -> Expression {
+        let size = 1 + self.rng.below(self.max_size);
+        self.generate_sized(size)
+    }
+
+    fn variable(&mut self) -> Symbol {
+        self.variables[self.rng.below(self.variables.len())]
+    }
+}
+
+impl Iterator for TermGenerator {
+    type Item = Expression;
+
+    fn next(&mut self) -> Option<Expression> {
+        Some(self.generate())
+    }
+}
+
+/// A small pseudorandom number generator (SplitMix64), so tests don't need a dependency.
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
@@ -1,6 +1,8 @@
+use crate::symbol::Symbol;
+
 #[derive(Debug, PartialEq)]
 pub enum Token {
-    Variable(String),
+    Variable(Symbol),
     Lambda,
     Dot,
     LeftParen,
@@ -21,22 +23,6 @@ impl<'a> TokenIterator<'a> {
     pub fn index(&self) -> usize {
         self.index
     }
-
-    pub fn end_of_expr(&self) -> usize {
-        let mut depth = 0;
-        let mut tokens = *self;
-        while let Some(token) = tokens.next() {
-            depth += match token {
-                Token::LeftParen => 1,
-                Token::RightParen => -1,
-                _ => 0,
-            };
-            if depth < 0 {
-                break;
-            }
-        }
-        tokens.index()
-    }
 }
 
 impl<'a> Iterator for TokenIterator<'a> {
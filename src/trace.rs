@@ -0,0 +1,133 @@
+use crate::error::LambdaError;
+use crate::expr::Expression;
+use crate::label::Label;
+use crate::redex::{contract_at, leftmost_outermost, Path};
+
+/// The terms an expression goes through when reduced one β-reduction at a time, in normal order.
+///
+/// See `trace`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trace<V: Label> {
+    terms: Vec<Expression<V>>,
+    /// The position of the redex contracted in each term but the last.
+    redexes: Vec<Path>,
+    complete: bool,
+}
+
+/// Reduce an expression in normal order, one β-reduction at a time, recording each term.
+///
+/// Reduction stops at the normal form, or after `max_steps` β-reductions. Unlike `evaluate`,
+/// the terms are not normalized, and functions are only renamed where needed to avoid capturing
+/// a variable.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{church, trace};
+///
+/// let trace = trace(church::succ() * church::zero(), 100);
+/// let terms: Vec<String> = trace.terms().iter().map(|term| term.to_string()).collect();
+/// assert_eq!(
+///     terms,
+///     ["(λn.λf.λx.f ((n f) x)) (λf.λx.x)", "λf.λx.f (((λf.λx.x) f) x)", "λf.λx.f ((λx.x) x)", "λf.λx.f x"]
+/// );
+/// assert_eq!(trace.steps(), 3);
+/// assert!(trace.is_complete());
+/// ```
+pub fn trace<V: Label>(expr: Expression<V>, max_steps: usize) -> Trace<V> {
+    let mut terms = vec![expr];
+    let mut redexes = Vec::new();
+    loop {
+        let term = terms.last().unwrap();
+        let Some(path) = leftmost_outermost(term) else {
+            break;
+        };
+        if redexes.len() == max_steps {
+            return Trace {
+                terms,
+                redexes,
+                complete: false,
+            };
+        }
+        let next = contract_at(term.clone(), &path).expect("the path leads to a redex");
+        terms.push(next);
+        redexes.push(path);
+    }
+    Trace {
+        terms,
+        redexes,
+        complete: true,
+    }
+}
+
+/// Reduce an expression in normal order like `trace`, but keep only the current term, and
+/// return its normal form.
+///
+/// An error is returned if the normal form isn't reached after `max_steps` β-reductions.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{church, reduce_normal_order, Expression};
+///
+/// let two = reduce_normal_order(church::succ() * church::one(), 100).unwrap();
+/// assert_eq!(two.to_string(), "λf.λx.f (f x)");
+///
+/// let omega: Expression = "(λx.x x) (λx.x x)".parse().unwrap();
+/// assert!(reduce_normal_order(omega, 100).is_err());
+/// ```
+pub fn reduce_normal_order<V: Label>(
+    expr: Expression<V>,
+    max_steps: usize,
+) -> Result<Expression<V>, LambdaError> {
+    let mut term = expr;
+    let mut steps = 0;
+    while let Some(path) = leftmost_outermost(&term) {
+        if steps == max_steps {
+            return Err(LambdaError::RuntimeError(format!(
+                "Reduction stopped after the maximum of {} β-reductions.",
+                max_steps
+            )));
+        }
+        term = contract_at(term, &path).expect("the path leads to a redex");
+        steps += 1;
+    }
+    Ok(term)
+}
+
+impl<V: Label> Trace<V> {
+    /// The terms of the reduction, starting with the input.
+    pub fn terms(&self) -> &[Expression<V>] {
+        &self.terms
+    }
+
+    /// The number of β-reductions performed.
+    pub fn steps(&self) -> usize {
+        self.redexes.len()
+    }
+
+    /// The position of the redex contracted in each term but the last.
+    pub fn redex_positions(&self) -> &[Path] {
+        &self.redexes
+    }
+
+    /// If the reduction reached a normal form, rather than stopping after the maximum number of
+    /// steps.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// The normal form of the expression, if the reduction reached it.
+    pub fn normal_form(&self) -> Option<&Expression<V>> {
+        self.terms.last().filter(|_| self.complete)
+    }
+
+    /// Each term along with the position of the redex contracted in it, and the position of
+    /// what the previous redex was contracted to.
+    pub(crate) fn contractions(
+        &self,
+    ) -> impl Iterator<Item = (&Expression<V>, Option<&Path>, Option<&Path>)> {
+        self.terms.iter().enumerate().map(|(i, term)| {
+            let contractum = i.checked_sub(1).map(|i| &self.redexes[i]);
+            (term, self.redexes.get(i), contractum)
+        })
+    }
+}
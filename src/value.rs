@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::expr::Expression;
+use crate::normalize::fresh_names;
+use crate::symbol::Symbol;
+
+/// What the environment of an evaluator binds variables to, and what neutral values hold as
+/// arguments, such as an already evaluated value or a thunk evaluated when first needed.
+pub(crate) trait Slot<'a>: Clone + Detach {
+    /// A slot holding a value that is already known.
+    fn from_value(value: Value<'a, Self>) -> Self;
+
+    /// The value in the slot, evaluating it first if needed.
+    fn value(&self) -> Value<'a, Self>;
+
+    /// Evaluate an expression to weak head normal form.
+    fn eval(expr: &'a Expression, env: &Env<Self>) -> Value<'a, Self>;
+}
+
+/// Taking apart a part of a value without recursion, see `Link`.
+pub(crate) trait Detach: Sized {
+    /// Move the parts only this refers to onto `links`, leaving it empty.
+    fn detach(&mut self, links: &mut Vec<Link<Self>>);
+}
+
+/// An environment, mapping variable names to the slots bound to them.
+pub(crate) type Env<S> = Option<Rc<Binding<S>>>;
+
+pub(crate) struct Binding<S: Detach> {
+    name: Symbol,
+    slot: S,
+    next: Env<S>,
+}
+
+/// An expression evaluated to weak head normal form.
+#[derive(Clone)]
+pub(crate) enum Value<'a, S: Detach> {
+    /// A function, along with the environment it was defined in.
+    Closure(Symbol, &'a Expression, Env<S>),
+    Neutral(Rc<Neutral<S>>),
+}
+
+/// A value that can't be reduced further because its head is a variable: the variable applied
+/// to any number of arguments, first to last.
+#[derive(Clone)]
+pub(crate) struct Neutral<S: Detach> {
+    pub(crate) head: Symbol,
+    pub(crate) args: Vec<S>,
+}
+
+pub(crate) fn bind<S: Detach>(name: Symbol, slot: S, env: &Env<S>) -> Env<S> {
+    Some(Rc::new(Binding {
+        name,
+        slot,
+        next: env.clone(),
+    }))
+}
+
+pub(crate) fn lookup<S: Detach + Clone>(name: Symbol, env: &Env<S>) -> Option<S> {
+    let mut env = env;
+    while let Some(binding) = env {
+        if binding.name == name {
+            return Some(binding.slot.clone());
+        }
+        env = &binding.next;
+    }
+    None
+}
+
+pub(crate) fn variable<'a, S: Detach>(name: Symbol) -> Value<'a, S> {
+    Value::Neutral(Rc::new(Neutral {
+        head: name,
+        args: Vec::new(),
+    }))
+}
+
+/// Evaluate an expression, giving every function of the result a fresh name, and return it
+/// along with the parameter in `expr` that each of those functions came from.
+pub(crate) fn evaluate_with_origins<'a, S: Slot<'a>>(
+    expr: &'a Expression,
+) -> (Expression, HashMap<Symbol, Symbol>) {
+    let mut fresh_names = fresh_names(expr);
+    let mut origins = HashMap::new();
+    let value = S::eval(expr, &None);
+    let result = quote(value, &mut fresh_names, &mut origins);
+    (result, origins)
+}
+
+/// Read a value back into an expression, evaluating under functions to reach normal form.
+///
+/// Each function is given a fresh parameter name, so the result contains no name shadowing, and
+/// the parameter it replaces is recorded in `origins`.
+fn quote<'a, S: Slot<'a>>(
+    value: Value<'a, S>,
+    fresh_names: &mut dyn Iterator<Item = Symbol>,
+    origins: &mut HashMap<Symbol, Symbol>,
+) -> Expression {
+    enum Task<'a, S: Slot<'a>> {
+        Quote(Value<'a, S>),
+        Force(S),
+        Function(Symbol),
+        Application,
+    }
+
+    let mut tasks = vec![Task::Quote(value)];
+    let mut results = Vec::new();
+    while let Some(task) = tasks.pop() {
+        match task {
+            Task::Quote(Value::Closure(param, body, env)) => {
+                let name = fresh_names.next().unwrap();
+                origins.insert(name, param);
+                let var = S::from_value(variable(name));
+                tasks.push(Task::Function(name));
+                tasks.push(Task::Quote(S::eval(body, &bind(param, var, &env))));
+            }
+            Task::Quote(Value::Neutral(neutral)) => {
+                // Apply the head to each argument in turn, forcing them in order.
+                results.push(Expression::Variable(neutral.head));
+                for arg in neutral.args.iter().rev() {
+                    tasks.push(Task::Application);
+                    tasks.push(Task::Force(arg.clone()));
+                }
+            }
+            Task::Force(slot) => tasks.push(Task::Quote(slot.value())),
+            Task::Function(name) => {
+                let body = results.pop().unwrap();
+                results.push(Expression::new_function(name, body));
+            }
+            Task::Application => {
+                let rhs = results.pop().unwrap();
+                let lhs = results.pop().unwrap();
+                results.push(Expression::new_application(lhs, rhs));
+            }
+        }
+    }
+    results.pop().unwrap()
+}
+
+/// A shared part of a value, to be taken apart if it is the last reference to it.
+///
+/// Values are linked into long chains through environments and the arguments of neutral values,
+/// which the default drop would follow recursively. Instead, dropping a binding or a neutral
+/// value moves the parts only it refers to onto a stack, and takes them apart from there.
+pub(crate) enum Link<S: Detach> {
+    Env(Rc<Binding<S>>),
+    Slot(S),
+}
+
+fn dismantle<S: Detach>(mut links: Vec<Link<S>>) {
+    while let Some(link) = links.pop() {
+        match link {
+            Link::Env(mut binding) => {
+                if let Some(binding) = Rc::get_mut(&mut binding) {
+                    binding.detach(&mut links);
+                }
+            }
+            Link::Slot(mut slot) => slot.detach(&mut links),
+        }
+    }
+}
+
+impl<S: Detach> Value<'_, S> {
+    /// Move the parts of the value only it refers to onto `links`.
+    pub(crate) fn detach(&mut self, links: &mut Vec<Link<S>>) {
+        match self {
+            Value::Closure(_, _, env) => links.extend(env.take().map(Link::Env)),
+            Value::Neutral(neutral) => {
+                if let Some(neutral) = Rc::get_mut(neutral) {
+                    neutral.detach(links);
+                }
+            }
+        }
+    }
+}
+
+impl<S: Detach> Binding<S> {
+    fn detach(&mut self, links: &mut Vec<Link<S>>) {
+        links.extend(self.next.take().map(Link::Env));
+        self.slot.detach(links);
+    }
+}
+
+impl<S: Detach> Neutral<S> {
+    fn detach(&mut self, links: &mut Vec<Link<S>>) {
+        links.extend(self.args.drain(..).map(Link::Slot));
+    }
+}
+
+impl<S: Detach> Drop for Binding<S> {
+    fn drop(&mut self) {
+        let mut links = Vec::new();
+        self.detach(&mut links);
+        dismantle(links);
+    }
+}
+
+impl<S: Detach> Drop for Neutral<S> {
+    fn drop(&mut self) {
+        let mut links = Vec::new();
+        self.detach(&mut links);
+        dismantle(links);
+    }
+}
@@ -0,0 +1,471 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::expr::Expression;
+use crate::label::Label;
+
+/// A traversal of an expression.
+///
+/// The methods are called in the order the parts of the expression are written, and each one
+/// does nothing by default, so a visitor only needs to implement the ones it cares about. See
+/// `Expression::visit`.
+pub trait Visitor<V: Label> {
+    fn visit_variable(&mut self, _label: &V) {}
+
+    /// Called on a function before its body is visited.
+    fn enter_function(&mut self, _param: &V) {}
+
+    /// Called on a function after its body is visited.
+    fn exit_function(&mut self, _param: &V) {}
+
+    /// Called on an application before its lhs and rhs are visited.
+    fn enter_application(&mut self) {}
+
+    /// Called on an application after its lhs and rhs are visited.
+    fn exit_application(&mut self) {}
+}
+
+/// A traversal of an expression that can change it.
+///
+/// Like `Visitor`, but every method gets mutable access. See `Expression::visit_mut`.
+pub trait VisitorMut<V: Label> {
+    /// Called on every expression before it is visited.
+    ///
+    /// The expression can be replaced, in which case the new expression is the one visited.
+    fn visit_expression(&mut self, _expr: &mut Expression<V>) {}
+
+    fn visit_variable(&mut self, _label: &mut V) {}
+
+    /// Called on a function before its body is visited.
+    fn enter_function(&mut self, _param: &mut V) {}
+
+    /// Called on a function after its body is visited.
+    fn exit_function(&mut self, _param: &mut V) {}
+
+    /// Called on an application before its lhs and rhs are visited.
+    fn enter_application(&mut self) {}
+
+    /// Called on an application after its lhs and rhs are visited.
+    fn exit_application(&mut self) {}
+}
+
+impl<V: Label> Expression<V> {
+    /// Walk through the expression, calling the methods of `visitor` on each part.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::{Expression, Symbol, Visitor};
+    ///
+    /// // Count the functions in an expression.
+    /// struct Functions(usize);
+    ///
+    /// impl Visitor<Symbol> for Functions {
+    ///     fn enter_function(&mut self, _param: &Symbol) {
+    ///         self.0 += 1;
+    ///     }
+    /// }
+    ///
+    /// let expr: Expression = "λx.(λy.y) x".parse().unwrap();
+    /// let mut functions = Functions(0);
+    /// expr.visit(&mut functions);
+    /// assert_eq!(functions.0, 2);
+    /// ```
+    pub fn visit(&self, visitor: &mut impl Visitor<V>) {
+        enum Task<'a, V: Label> {
+            Visit(&'a Expression<V>),
+            ExitFunction(&'a V),
+            ExitApplication,
+        }
+
+        let mut tasks = vec![Task::Visit(self)];
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit(Expression::Variable(label)) => visitor.visit_variable(label),
+                Task::Visit(Expression::Function(param, body)) => {
+                    visitor.enter_function(param);
+                    tasks.push(Task::ExitFunction(param));
+                    tasks.push(Task::Visit(body));
+                }
+                Task::Visit(Expression::Application(lhs, rhs)) => {
+                    visitor.enter_application();
+                    tasks.push(Task::ExitApplication);
+                    tasks.push(Task::Visit(rhs));
+                    tasks.push(Task::Visit(lhs));
+                }
+                Task::ExitFunction(param) => visitor.exit_function(param),
+                Task::ExitApplication => visitor.exit_application(),
+            }
+        }
+    }
+
+    /// Walk through the expression, calling the methods of `visitor` on each part, and letting
+    /// it change them.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::{Expression, Symbol, VisitorMut};
+    ///
+    /// // Replace every `id` with the identity function.
+    /// struct Inline;
+    ///
+    /// impl VisitorMut<Symbol> for Inline {
+    ///     fn visit_expression(&mut self, expr: &mut Expression) {
+    ///         if matches!(expr, Expression::Variable(label) if *label == "id") {
+    ///             *expr = "λx.x".parse().unwrap();
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut expr: Expression = "id (id y)".parse().unwrap();
+    /// expr.visit_mut(&mut Inline);
+    /// assert_eq!(expr.to_string(), "(λx.x) ((λx.x) y)");
+    /// ```
+    pub fn visit_mut(&mut self, visitor: &mut impl VisitorMut<V>) {
+        enum Task<'a, V: Label> {
+            Visit(&'a mut Expression<V>),
+            ExitFunction(&'a mut V),
+            ExitApplication,
+        }
+
+        let mut tasks = vec![Task::Visit(self)];
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit(expr) => {
+                    visitor.visit_expression(expr);
+                    match expr {
+                        Expression::Variable(label) => visitor.visit_variable(label),
+                        Expression::Function(param, body) => {
+                            visitor.enter_function(param);
+                            tasks.push(Task::ExitFunction(param));
+                            tasks.push(Task::Visit(body));
+                        }
+                        Expression::Application(lhs, rhs) => {
+                            visitor.enter_application();
+                            tasks.push(Task::ExitApplication);
+                            tasks.push(Task::Visit(rhs));
+                            tasks.push(Task::Visit(lhs));
+                        }
+                    }
+                }
+                Task::ExitFunction(param) => visitor.exit_function(param),
+                Task::ExitApplication => visitor.exit_application(),
+            }
+        }
+    }
+
+    /// Combine the parts of the expression bottom up into a single value.
+    ///
+    /// Each variable is turned into a value with `variable`, and the values of the subexpressions
+    /// of functions and applications are combined with `function` and `application`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use lambda::Expression;
+    ///
+    /// let expr: Expression = "λx.λy.x (y x)".parse().unwrap();
+    /// let variables = expr.fold(|_| 1, |_, body| body, |lhs, rhs| lhs + rhs);
+    /// assert_eq!(variables, 3);
+    /// ```
+    pub fn fold<T>(
+        &self,
+        mut variable: impl FnMut(&V) -> T,
+        mut function: impl FnMut(&V, T) -> T,
+        mut application: impl FnMut(T, T) -> T,
+    ) -> T {
+        enum Task<'a, V: Label> {
+            Visit(&'a Expression<V>),
+            Function(&'a V),
+            Application,
+        }
+
+        let mut tasks = vec![Task::Visit(self)];
+        let mut results = Vec::new();
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit(Expression::Variable(label)) => results.push(variable(label)),
+                Task::Visit(Expression::Function(param, body)) => {
+                    tasks.push(Task::Function(param));
+                    tasks.push(Task::Visit(body));
+                }
+                Task::Visit(Expression::Application(lhs, rhs)) => {
+                    tasks.push(Task::Application);
+                    tasks.push(Task::Visit(rhs));
+                    tasks.push(Task::Visit(lhs));
+                }
+                Task::Function(param) => {
+                    let body = results.pop().unwrap();
+                    results.push(function(param, body));
+                }
+                Task::Application => {
+                    let rhs = results.pop().unwrap();
+                    let lhs = results.pop().unwrap();
+                    results.push(application(lhs, rhs));
+                }
+            }
+        }
+        results.pop().unwrap()
+    }
+}
+
+/// A visitor counting the variables, functions and applications in an expression.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{Expression, Size};
+///
+/// let expr: Expression = "λx.x y".parse().unwrap();
+/// assert_eq!(Size::of(&expr), 4);
+/// ```
+#[derive(Debug, Default)]
+pub struct Size {
+    count: usize,
+}
+
+impl Size {
+    pub fn of<V: Label>(expr: &Expression<V>) -> usize {
+        let mut size = Size::default();
+        expr.visit(&mut size);
+        size.count()
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl<V: Label> Visitor<V> for Size {
+    fn visit_variable(&mut self, _label: &V) {
+        self.count += 1;
+    }
+
+    fn enter_function(&mut self, _param: &V) {
+        self.count += 1;
+    }
+
+    fn enter_application(&mut self) {
+        self.count += 1;
+    }
+}
+
+/// A visitor finding the depth of an expression, i.e. the number of parts on the longest path
+/// from the whole expression down to a variable.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{Depth, Expression};
+///
+/// let expr: Expression = "λx.x y".parse().unwrap();
+/// assert_eq!(Depth::of(&expr), 3);
+/// ```
+#[derive(Debug, Default)]
+pub struct Depth {
+    current: usize,
+    max: usize,
+}
+
+impl Depth {
+    pub fn of<V: Label>(expr: &Expression<V>) -> usize {
+        let mut depth = Depth::default();
+        expr.visit(&mut depth);
+        depth.max()
+    }
+
+    pub fn max(&self) -> usize {
+        self.max
+    }
+}
+
+impl<V: Label> Visitor<V> for Depth {
+    fn visit_variable(&mut self, _label: &V) {
+        self.max = self.max.max(self.current + 1);
+    }
+
+    fn enter_function(&mut self, _param: &V) {
+        self.current += 1;
+    }
+
+    fn exit_function(&mut self, _param: &V) {
+        self.current -= 1;
+    }
+
+    fn enter_application(&mut self) {
+        self.current += 1;
+    }
+
+    fn exit_application(&mut self) {
+        self.current -= 1;
+    }
+}
+
+/// A visitor collecting the variables that occur free in an expression, i.e. not inside a
+/// function with that parameter.
+///
+/// Each variable is collected once, in the order of its first free occurrence.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{Expression, FreeVariables};
+///
+/// let expr: Expression = "λx.y x (λy.y z) y".parse().unwrap();
+/// assert_eq!(FreeVariables::of(&expr), ["y", "z"]);
+/// ```
+#[derive(Debug)]
+pub struct FreeVariables<V: Label> {
+    /// The number of enclosing functions binding each parameter.
+    scope: HashMap<V, usize>,
+    seen: HashSet<V>,
+    variables: Vec<V>,
+}
+
+impl<V: Label> FreeVariables<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn of(expr: &Expression<V>) -> Vec<V> {
+        let mut free_variables = Self::new();
+        expr.visit(&mut free_variables);
+        free_variables.into_variables()
+    }
+
+    pub fn into_variables(self) -> Vec<V> {
+        self.variables
+    }
+}
+
+impl<V: Label> Default for FreeVariables<V> {
+    fn default() -> Self {
+        Self {
+            scope: HashMap::new(),
+            seen: HashSet::new(),
+            variables: Vec::new(),
+        }
+    }
+}
+
+impl<V: Label> Visitor<V> for FreeVariables<V> {
+    fn visit_variable(&mut self, label: &V) {
+        if !self.scope.contains_key(label) && self.seen.insert(label.clone()) {
+            self.variables.push(label.clone());
+        }
+    }
+
+    fn enter_function(&mut self, param: &V) {
+        *self.scope.entry(param.clone()).or_default() += 1;
+    }
+
+    fn exit_function(&mut self, param: &V) {
+        let count = self.scope.get_mut(param).unwrap();
+        *count -= 1;
+        if *count == 0 {
+            self.scope.remove(param);
+        }
+    }
+}
+
+/// A visitor counting the free occurrences of a variable in an expression.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{Expression, Occurrences};
+///
+/// let expr: Expression = "λx.y x (λy.y) y".parse().unwrap();
+/// assert_eq!(Occurrences::of("y", &expr), 2);
+/// ```
+#[derive(Debug)]
+pub struct Occurrences<V: Label> {
+    name: V,
+    /// The number of enclosing functions with the variable as their parameter.
+    shadowed: usize,
+    count: usize,
+}
+
+impl<V: Label> Occurrences<V> {
+    pub fn new(name: impl Into<V>) -> Self {
+        Self {
+            name: name.into(),
+            shadowed: 0,
+            count: 0,
+        }
+    }
+
+    pub fn of(name: impl Into<V>, expr: &Expression<V>) -> usize {
+        let mut occurrences = Self::new(name);
+        expr.visit(&mut occurrences);
+        occurrences.count()
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl<V: Label> Visitor<V> for Occurrences<V> {
+    fn visit_variable(&mut self, label: &V) {
+        if *label == self.name && self.shadowed == 0 {
+            self.count += 1;
+        }
+    }
+
+    fn enter_function(&mut self, param: &V) {
+        if *param == self.name {
+            self.shadowed += 1;
+        }
+    }
+
+    fn exit_function(&mut self, param: &V) {
+        if *param == self.name {
+            self.shadowed -= 1;
+        }
+    }
+}
+
+/// A visitor collecting the variables bound in an expression, i.e. the parameters of its
+/// functions.
+///
+/// Each variable is collected once, in the order of the first function binding it.
+///
+/// # Examples
+/// ```rust
+/// use lambda::{BoundVariables, Expression};
+///
+/// let expr: Expression = "λx.y x (λy.λx.z)".parse().unwrap();
+/// assert_eq!(BoundVariables::of(&expr), ["x", "y"]);
+/// ```
+#[derive(Debug)]
+pub struct BoundVariables<V: Label> {
+    seen: HashSet<V>,
+    variables: Vec<V>,
+}
+
+impl<V: Label> BoundVariables<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn of(expr: &Expression<V>) -> Vec<V> {
+        let mut bound_variables = Self::new();
+        expr.visit(&mut bound_variables);
+        bound_variables.into_variables()
+    }
+
+    pub fn into_variables(self) -> Vec<V> {
+        self.variables
+    }
+}
+
+impl<V: Label> Default for BoundVariables<V> {
+    fn default() -> Self {
+        Self {
+            seen: HashSet::new(),
+            variables: Vec::new(),
+        }
+    }
+}
+
+impl<V: Label> Visitor<V> for BoundVariables<V> {
+    fn enter_function(&mut self, param: &V) {
+        if self.seen.insert(param.clone()) {
+            self.variables.push(param.clone());
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::{Expression, Term, TermArena};
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+#[test]
+fn alpha_equivalent_terms_are_shared() {
+    let mut arena = TermArena::new();
+    let id = arena.intern(&parse("λx.λy.x (y z)"));
+    assert_eq!(id, arena.intern(&parse("λa.λb.a (b z)")));
+    assert_ne!(id, arena.intern(&parse("λa.λb.b (a z)")));
+    assert_ne!(id, arena.intern(&parse("λa.λb.a (b w)")));
+}
+
+#[test]
+fn identical_subterms_are_stored_once() {
+    let mut arena = TermArena::new();
+    arena.intern(&parse("(x y) (x y)"));
+    // x, y, x y and the whole application.
+    assert_eq!(arena.len(), 4);
+
+    let id = arena.intern(&parse("λx.x"));
+    let body = arena.bound(0);
+    assert_eq!(arena.get(id), &Term::Function(body));
+}
+
+#[test]
+fn round_trip_normalizes() {
+    let mut arena = TermArena::new();
+    for code in ["x", "λx.λy.λx.x y", church::PRED, "λf.f (y x)", "λx.x 1"] {
+        let id = arena.intern(&parse(code));
+        assert_eq!(arena.to_expression(id), parse(code).normalize());
+    }
+}
+
+#[test]
+fn evaluate() {
+    let exprs = [
+        parse("(λx.λy.x) y"),
+        parse("(λx.λy.y) ((λx.x x) (λx.x x))"),
+        church::succ() * church::two(),
+        church::pred() * church::three(),
+        church::mul() * church::two() * church::three(),
+        church::pow() * church::two() * church::three(),
+    ];
+    let mut arena = TermArena::new();
+    for expr in exprs {
+        let id = arena.intern(&expr);
+        let normal = arena.evaluate(id);
+        assert_eq!(arena.to_expression(normal), lambda::evaluate_lazy(expr));
+    }
+}
+
+#[test]
+fn evaluate_with_sharing() {
+    let mut arena = TermArena::new();
+    let four = church::pow() * church::two() * church::two();
+    let sixteen = church::pow() * church::two() * four;
+    let id = arena.intern(&(church::pow() * church::two() * sixteen));
+    let normal = arena.evaluate(id);
+    let expected = arena.to_expression(normal).to_string();
+    assert_eq!(expected.matches('(').count(), 65535);
+}
@@ -0,0 +1,80 @@
+use std::thread;
+use std::time::Duration;
+
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::{evaluate_cancellable, CancellationToken, Expression, LambdaError};
+
+const OMEGA: &str = "(λx.x x) (λx.x x)";
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+fn error_message(result: Result<Expression, LambdaError>) -> String {
+    match result {
+        Err(LambdaError::RuntimeError(message)) => message,
+        result => panic!("Expected a runtime error, got {:?}", result),
+    }
+}
+
+#[test]
+fn finishes_when_not_cancelled() {
+    let token = CancellationToken::new();
+    let expr = church::pow() * church::two() * church::three();
+    assert_eq!(
+        evaluate_cancellable(expr.clone(), &token).unwrap(),
+        expr.evaluate()
+    );
+}
+
+#[test]
+fn cancel_from_another_thread() {
+    let token = CancellationToken::new();
+    let worker = {
+        let token = token.clone();
+        thread::spawn(move || evaluate_cancellable(parse(OMEGA), &token))
+    };
+    thread::sleep(Duration::from_millis(20));
+    token.cancel();
+    let message = error_message(worker.join().unwrap());
+    assert!(message.starts_with("Evaluation cancelled"), "{}", message);
+}
+
+#[test]
+fn deadline() {
+    let token = CancellationToken::with_timeout(Duration::from_millis(20));
+    let message = error_message(evaluate_cancellable(parse(OMEGA), &token));
+    assert!(message.starts_with("Evaluation timed out"), "{}", message);
+    assert!(token.is_cancelled());
+}
+
+#[test]
+fn already_cancelled() {
+    let token = CancellationToken::new();
+    token.cancel();
+    assert!(evaluate_cancellable(parse(OMEGA), &token).is_err());
+    // Terms that need few reductions may finish before the token is checked.
+    assert!(evaluate_cancellable(parse("(λx.x) y"), &token).is_ok());
+}
+
+#[test]
+fn copying_without_capture() {
+    // This used to grow forever by copying values into themselves, without any β-reductions.
+    let token = CancellationToken::with_timeout(Duration::from_millis(100));
+    let expr = parse("(λy.y y) (λf.((a b) f) c)");
+    assert_eq!(
+        evaluate_cancellable(expr, &token).unwrap(),
+        parse("((a b) (λf.((a b) f) c)) c").normalize()
+    );
+}
+
+#[test]
+fn deadline_between_reductions() {
+    // Only a few hundred β-reductions, but they copy values of exponentially growing size.
+    let expr = church::two() * church::two() * church::two() * church::two() * church::two();
+    let token = CancellationToken::with_timeout(Duration::from_millis(100));
+    let message = error_message(evaluate_cancellable(expr, &token));
+    assert!(message.starts_with("Evaluation timed out"), "{}", message);
+}
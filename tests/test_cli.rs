@@ -0,0 +1,187 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use pretty_assertions::assert_eq;
+
+fn evaluate(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_evaluate"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to run evaluate.");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn expression_from_arguments() {
+    let output = evaluate(&["(λn.λf.λx.f", "(n f x))", "(λf.λx.x)"], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "λf.λx.f x\n");
+}
+
+#[test]
+fn expression_from_stdin_and_file() {
+    let output = evaluate(&[], "(λx.x)\n  y\n");
+    assert_eq!(stdout(&output), "y\n");
+    let output = evaluate(&["--file", "-"], "(λx.x) y");
+    assert_eq!(stdout(&output), "y\n");
+
+    // Include the process id, so that test runs at the same time don't share the file.
+    let file_name = format!("lambda_test_cli_input_{}.txt", std::process::id());
+    let path = std::env::temp_dir().join(file_name);
+    std::fs::write(&path, "(λx.λy.x) a b").unwrap();
+    let output = evaluate(&["-f", path.to_str().unwrap()], "");
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(stdout(&output), "a\n");
+}
+
+#[test]
+fn names() {
+    let code = "(λx.λy.x) (λz.z)";
+    assert_eq!(stdout(&evaluate(&[code], "")), "λy.λz.z\n");
+    assert_eq!(stdout(&evaluate(&["--numbered", code], "")), "λ1.λ2.2\n");
+    assert_eq!(
+        stdout(&evaluate(&["--no-normalize", code], "")),
+        "λ2.λ4.4\n"
+    );
+}
+
+#[test]
+fn strategies() {
+    let code = "(λm.λn.λf.λx.m f (n f x)) (λf.λx.f x) (λf.λx.f x)";
+    for strategy in ["eager", "normal", "lazy", "nbe", "optimal"] {
+        let output = evaluate(&["--strategy", strategy, "--numbered", code], "");
+        assert_eq!(stdout(&output), "λ1.λ2.1 (1 2)\n", "{}", strategy);
+    }
+    for strategy in ["eager", "normal", "lazy", "nbe", "optimal"] {
+        let output = evaluate(&["--strategy", strategy, code], "");
+        assert_eq!(stdout(&output), "λf.λx.f (f x)\n", "{}", strategy);
+    }
+    let output = evaluate(&["--strategy=normal", "(λx.y) ((λx.x x) (λx.x x))"], "");
+    assert_eq!(stdout(&output), "y\n");
+}
+
+#[test]
+fn readable_names_across_flags() {
+    let code = "(λf.λx.f x) (λy.y)";
+    let mut runs = vec![vec!["--stats"], vec!["--max-steps", "10"]];
+    for strategy in ["eager", "normal", "lazy", "nbe", "optimal"] {
+        runs.push(vec!["--strategy", strategy]);
+    }
+    for mut args in runs {
+        args.push(code);
+        let output = evaluate(&args, "");
+        let result = stdout(&output);
+        assert_eq!(result.lines().next(), Some("λx.x"), "{:?}", args);
+    }
+}
+
+#[test]
+fn max_steps() {
+    let omega = "(λx.x x) (λx.x x)";
+    for strategy in ["eager", "normal"] {
+        let output = evaluate(&["--strategy", strategy, "--max-steps", "10", omega], "");
+        assert_eq!(output.status.code(), Some(3));
+        assert!(stderr(&output).starts_with("RuntimeError: "));
+    }
+    // Copies of a function inside each other used to grow forever without any β-reductions.
+    for code in [
+        "(λy.y y) (λf.((a b) f) c)",
+        "λg.λx.λg.(λf.(λy.y y) (λf.((y g) f) x)) (λy.x)",
+    ] {
+        let output = evaluate(&["--max-steps", "5", code], "");
+        assert_eq!(output.status.code(), Some(0), "{}", code);
+        let output = evaluate(&["--max-steps", "1", code], "");
+        assert_eq!(output.status.code(), Some(3), "{}", code);
+    }
+}
+
+#[test]
+fn trace() {
+    let code = "(λx.x) ((λy.y) z)";
+    let output = evaluate(&["--trace", "--highlight", "none", code], "");
+    assert_eq!(stdout(&output), "  (λx.x) ((λy.y) z)\n→ (λy.y) z\n→ z\n");
+    let output = evaluate(
+        &["--trace", "--highlight=none", "--max-steps", "1", code],
+        "",
+    );
+    assert_eq!(stdout(&output), "  (λx.x) ((λy.y) z)\n→ (λy.y) z\n→ …\n");
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn trace_highlighting() {
+    // Output to a pipe is marked with plain text.
+    let output = evaluate(&["--trace", "(λx.x) y"], "");
+    assert_eq!(stdout(&output), "  (λx.x) y\n  ^^^^^^^^\n→ y\n  ~\n");
+    let output = evaluate(&["--trace", "--highlight", "color", "(λx.x) y"], "");
+    assert_eq!(
+        stdout(&output),
+        "  \x1b[1;31m(λx.x) y\x1b[0m\n→ \x1b[32my\x1b[0m\n"
+    );
+}
+
+#[test]
+fn output_formats() {
+    let output = evaluate(&["--output-format", "latex", "(λx.x) (λy.y y)"], "");
+    assert_eq!(stdout(&output), "\\lambda y.\\,y\\;y\n");
+    let output = evaluate(&["--output-format", "dot", "(λx.x) z"], "");
+    assert_eq!(
+        stdout(&output),
+        "digraph {\n    node [shape=none];\n    n0 [label=\"z\"];\n}\n"
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_output() {
+    let output = evaluate(&["--output-format", "json", "(λx.x) z"], "");
+    assert_eq!(stdout(&output), "{\"type\":\"variable\",\"name\":\"z\"}\n");
+}
+
+#[test]
+fn syntax_error() {
+    let output = evaluate(&["λx."], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr(&output),
+        "SyntaxError: The code does not contain an expression.\n"
+    );
+}
+
+#[test]
+fn usage_errors() {
+    for args in [
+        &["--strategy", "fast", "x"][..],
+        &["--max-steps", "many", "x"],
+        &["--max-steps"],
+        &["--unknown", "x"],
+        &["--trace", "--strategy", "eager", "x"],
+        &["--strategy", "lazy", "--max-steps", "3", "x"],
+        &["--file", "missing.txt", "x"],
+        &["--file", "/nonexistent/lambda/input"],
+    ] {
+        let output = evaluate(args, "");
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+        assert!(stdout(&output).is_empty());
+    }
+    let output = evaluate(&["--help"], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).starts_with("Usage: evaluate"));
+}
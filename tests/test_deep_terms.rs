@@ -0,0 +1,107 @@
+use lambda::church;
+use lambda::{Depth, Expression, FreeVariables, Size};
+
+const DEPTH: usize = 100_000;
+
+/// The Church numeral for `n`, built without parsing.
+fn numeral(n: usize) -> Expression {
+    let mut body = Expression::new_variable("x");
+    for _ in 0..n {
+        body = Expression::new_application(Expression::new_variable("f"), body);
+    }
+    Expression::new_function(
+        "f".to_string(),
+        Expression::new_function("x".to_string(), body),
+    )
+}
+
+#[test]
+fn display_clone_and_compare() {
+    let expr = numeral(DEPTH);
+    let copy = expr.clone();
+    assert_eq!(expr, copy);
+    assert_ne!(expr, numeral(DEPTH - 1));
+    assert_eq!(expr.to_string().matches('f').count(), DEPTH + 1);
+}
+
+#[test]
+fn normalize() {
+    let normalized = numeral(DEPTH).normalize();
+    assert!(normalized.to_string().starts_with("λ1.λ2.1 (1 (1"));
+}
+
+#[test]
+fn evaluate() {
+    let result = (church::succ() * numeral(DEPTH)).evaluate();
+    assert_eq!(result, numeral(DEPTH + 1).normalize());
+}
+
+#[test]
+fn evaluate_lazy() {
+    let result = lambda::evaluate_lazy(church::succ() * numeral(DEPTH));
+    assert_eq!(result, numeral(DEPTH + 1).normalize());
+
+    // A long chain of thunks, each forcing the next.
+    let applied = numeral(DEPTH) * Expression::new_variable("g") * Expression::new_variable("y");
+    let result = lambda::evaluate_lazy(applied);
+    assert_eq!(result.to_string().matches('g').count(), DEPTH);
+}
+
+#[test]
+fn evaluate_nbe() {
+    let result = lambda::evaluate_nbe(church::succ() * numeral(DEPTH));
+    assert_eq!(result, numeral(DEPTH + 1).normalize());
+
+    let applied = numeral(DEPTH) * Expression::new_variable("g") * Expression::new_variable("y");
+    let result = lambda::evaluate_nbe(applied);
+    assert_eq!(result.to_string().matches('g').count(), DEPTH);
+}
+
+#[test]
+fn evaluate_optimal() {
+    // Every variable is used at most once, as reading back a numeral this large is slow.
+    let mut expr = Expression::new_variable("x");
+    for _ in 0..DEPTH {
+        let identity = Expression::new_function("y".to_string(), Expression::new_variable("y"));
+        expr = identity * expr;
+    }
+    assert_eq!(lambda::evaluate_optimal(expr).unwrap().to_string(), "x");
+
+    let mut expr = Expression::new_variable("x");
+    for _ in 0..DEPTH {
+        expr = Expression::new_function("y".to_string(), Expression::new_variable("f") * expr);
+    }
+    let result = lambda::evaluate_optimal(expr.clone()).unwrap();
+    assert_eq!(result, expr.normalize());
+}
+
+#[test]
+fn long_application_spine() {
+    let code = vec!["x"; DEPTH].join(" ");
+    let expr: Expression = code.parse().unwrap();
+    assert_eq!(expr.clone().normalize(), expr);
+    assert_eq!(expr.to_string().matches('x').count(), DEPTH);
+
+    let identities = vec!["(λx.x)"; DEPTH].join(" ");
+    let expr: Expression = identities.parse().unwrap();
+    assert_eq!(expr.evaluate().to_string(), "λ1.1");
+}
+
+#[test]
+fn parse() {
+    let code = format!("λf.λx.{}x{}", "f (".repeat(DEPTH), ")".repeat(DEPTH));
+    let expr: Expression = code.parse().unwrap();
+    assert_eq!(expr, numeral(DEPTH));
+
+    let code = format!("{}x{}", "(λy.".repeat(DEPTH), ")".repeat(DEPTH));
+    let expr: Expression = code.parse().unwrap();
+    assert_eq!(Depth::of(&expr), DEPTH + 1);
+}
+
+#[test]
+fn visit() {
+    let expr = numeral(DEPTH);
+    assert_eq!(Size::of(&expr), 2 * DEPTH + 3);
+    assert_eq!(Depth::of(&expr), DEPTH + 3);
+    assert!(FreeVariables::of(&expr).is_empty());
+}
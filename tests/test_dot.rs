@@ -0,0 +1,70 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::{DotOptions, Expression};
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+const BINDINGS: DotOptions = DotOptions {
+    binding_edges: true,
+};
+
+#[test]
+fn variable() {
+    assert_eq!(
+        parse("x").to_dot(),
+        "digraph {\n    node [shape=none];\n    n0 [label=\"x\"];\n}\n"
+    );
+}
+
+#[test]
+fn one_node_per_subexpression() {
+    let expr = church::pred();
+    let dot = expr.to_dot();
+    assert_eq!(dot.matches(" [label=").count(), expr.size());
+    assert_eq!(dot.matches(" -> ").count(), expr.size() - 1);
+    assert_eq!(dot.matches("\"@\"").count(), expr.applications());
+    assert_eq!(dot.matches("\"λ").count(), expr.abstractions());
+}
+
+#[test]
+fn binding_edges() {
+    let dot = parse("λx.(λx.x) x y").to_dot_with(&BINDINGS);
+    assert_eq!(
+        dot,
+        r#"digraph {
+    node [shape=none];
+    n0 [label="λx"];
+    n1 [label="@"];
+    n0 -> n1;
+    n2 [label="@"];
+    n1 -> n2;
+    n3 [label="λx"];
+    n2 -> n3;
+    n4 [label="x"];
+    n3 -> n4;
+    n4 -> n3 [style=dashed, constraint=false, label="1"];
+    n5 [label="x"];
+    n2 -> n5;
+    n5 -> n0 [style=dashed, constraint=false, label="1"];
+    n6 [label="y"];
+    n1 -> n6;
+}
+"#
+    );
+}
+
+#[test]
+fn de_bruijn_indices() {
+    let dot = church::two().to_dot_with(&BINDINGS);
+    assert_eq!(dot.matches("label=\"2\"").count(), 2);
+    assert_eq!(dot.matches("label=\"1\"").count(), 1);
+}
+
+#[test]
+fn escapes_labels() {
+    let expr: Expression<String> = Expression::new_variable("say \"hi\"");
+    assert!(expr.to_dot().contains(r#"n0 [label="say \"hi\""];"#));
+}
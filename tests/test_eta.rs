@@ -0,0 +1,56 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::{beta_eta_equivalent, eta_expand, eta_reduce, evaluate_beta_eta, Expression};
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+fn check_eta_reduced(code: &str, expected: &str) {
+    assert_eq!(eta_reduce(parse(code)).to_string(), expected);
+}
+
+#[test]
+fn simple_reductions() {
+    check_eta_reduced("λx.f x", "f");
+    check_eta_reduced("λx.λy.f x y", "f");
+    check_eta_reduced("λx.λy.x y", "λx.x");
+    check_eta_reduced("g (λx.f x)", "g f");
+}
+
+#[test]
+fn bound_variable_must_not_be_free_in_function() {
+    check_eta_reduced("λx.x x", "λx.x x");
+    check_eta_reduced("λx.(f x) x", "λx.(f x) x");
+    check_eta_reduced("λx.f y", "λx.f y");
+    check_eta_reduced("λx.(λx.x) x", "λx.x");
+}
+
+#[test]
+fn expansion() {
+    assert_eq!(eta_expand(parse("f")).to_string(), "λx.f x");
+    assert_eq!(eta_expand(parse("λy.x y")).to_string(), "λx'.(λy.x y) x'");
+    assert_eq!(eta_reduce(eta_expand(parse("f x"))).to_string(), "f x");
+}
+
+#[test]
+fn beta_eta_normal_form() {
+    assert_eq!(
+        evaluate_beta_eta(parse("(λx.λy.x y) (λz.z)")),
+        parse("λz.z").normalize()
+    );
+    // The Church numeral one is η-equivalent to the identity function.
+    assert_eq!(
+        evaluate_beta_eta(church::succ() * church::zero()),
+        parse("λz.z").normalize()
+    );
+}
+
+#[test]
+fn equivalence() {
+    assert!(beta_eta_equivalent(parse("λx.λy.x y"), parse("λx.x")));
+    assert!(beta_eta_equivalent(church::one(), parse("λf.f")));
+    assert!(!beta_eta_equivalent(church::two(), parse("λf.f")));
+    assert!(!beta_eta_equivalent(parse("λx.x x"), parse("λx.x")));
+}
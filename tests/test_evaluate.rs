@@ -34,6 +34,15 @@ fn single_function() {
     check_is_equal("λx.x", "λx.x");
 }
 
+#[test]
+fn copies_of_functions_do_not_capture() {
+    check_is_equal(
+        "(λf.λx.f f) (λf.λg.(λx.(f g) z) (λz.λg.x))",
+        "λa.λb.(b z) z",
+    );
+    check_is_equal("(λy.y y) (λf.((a b) f) c)", "((a b) (λf.((a b) f) c)) c");
+}
+
 #[test]
 fn single_application() {
     check_is_equal("(λx.x) y", "y");
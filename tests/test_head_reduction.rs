@@ -0,0 +1,64 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::{hnf, whnf, Expression};
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+fn check_whnf(code: &str, expected: &str) {
+    assert_eq!(whnf(parse(code)).to_string(), expected);
+}
+
+fn check_hnf(code: &str, expected: &str) {
+    assert_eq!(hnf(parse(code)).to_string(), expected);
+}
+
+const OMEGA: &str = "((λx.x x) (λx.x x))";
+/// An infinite stream of `x`s: `Y (λs.cons x s)`, with `cons = λh.λt.λc.c h t`.
+const XS: &str = "(λf.(λx.f (x x)) (λx.f (x x))) (λs.(λh.λt.λc.c h t) x s)";
+
+#[test]
+fn already_in_whnf() {
+    check_whnf("x", "x");
+    check_whnf("x ((λy.y) z)", "x ((λy.y) z)");
+    check_whnf("λx.(λy.y) x", "λx.(λy.y) x");
+}
+
+#[test]
+fn reduces_head() {
+    check_whnf("(λx.x) y", "y");
+    check_whnf("(λx.λy.x) a b", "a");
+    check_whnf(&format!("(λx.λy.y) {} z", OMEGA), "z");
+    check_whnf(&format!("(λx.x) (f {})", OMEGA), &format!("f {}", OMEGA));
+}
+
+#[test]
+fn avoids_capture() {
+    check_whnf("(λx.λy.x) y", "λ1.y");
+    check_whnf("(λx.λ1.λy.x 1 y) y", "λ1.λ2.(y 1) 2");
+    check_whnf("(λx.λx.x) y", "λx.x");
+}
+
+#[test]
+fn reduces_under_functions() {
+    check_hnf("λx.(λy.λz.y z) x", "λx.λz.x z");
+    check_hnf(&format!("λx.x {}", OMEGA), &format!("λx.x {}", OMEGA));
+    assert_eq!(
+        hnf(church::succ() * church::one()),
+        parse("λf.λx.f ((λf.λx.f x) f x)")
+    );
+}
+
+#[test]
+fn head_of_infinite_stream() {
+    let head = parse(&format!("({}) (λh.λt.h)", XS));
+    assert_eq!(whnf(head), parse("x"));
+
+    let second = parse(&format!("({}) (λh.λt.t) (λh.λt.h)", XS));
+    assert_eq!(whnf(second), parse("x"));
+
+    let stream = hnf(parse(XS));
+    assert!(stream.to_string().starts_with("λc.(c x) "));
+}
@@ -0,0 +1,166 @@
+#![cfg(feature = "serde")]
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+use lambda::church;
+use lambda::testing::TermGenerator;
+use lambda::{Depth, Expression, FlatExpression, LambdaError};
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+#[test]
+fn format() {
+    let value = serde_json::to_value(parse("(λx.x) y")).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "type": "application",
+            "lhs": {
+                "type": "function",
+                "param": "x",
+                "body": {"type": "variable", "name": "x"},
+            },
+            "rhs": {"type": "variable", "name": "y"},
+        })
+    );
+}
+
+#[test]
+fn round_trip() {
+    for expr in TermGenerator::new(10).with_max_size(40).take(500) {
+        let json = serde_json::to_string(&expr).unwrap();
+        assert_eq!(serde_json::from_str::<Expression>(&json).unwrap(), expr);
+    }
+    let expr = church::pred();
+    let value = serde_json::to_value(&expr).unwrap();
+    assert_eq!(serde_json::from_value::<Expression>(value).unwrap(), expr);
+}
+
+#[test]
+fn other_labels() {
+    let expr: Expression<u32> = Expression::new_function(1u32, Expression::new_variable(1u32));
+    let json = serde_json::to_string(&expr).unwrap();
+    assert_eq!(
+        json,
+        r#"{"type":"function","param":1,"body":{"type":"variable","name":1}}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<Expression<u32>>(&json).unwrap(),
+        expr
+    );
+}
+
+#[test]
+fn escaped_names() {
+    let json = r#"{"type":"variable","name":"x\u0027"}"#;
+    assert_eq!(
+        serde_json::from_str::<Expression>(json).unwrap(),
+        parse("x'")
+    );
+}
+
+#[test]
+fn invalid() {
+    let json = r#"{"type":"abstraction","param":"x","body":{"type":"variable","name":"x"}}"#;
+    assert!(serde_json::from_str::<Expression>(json).is_err());
+    let json = r#"{"type":"function","param":"x"}"#;
+    assert!(serde_json::from_str::<Expression>(json).is_err());
+}
+
+#[test]
+fn errors() {
+    let error = "λx".parse::<Expression>().unwrap_err();
+    let value = serde_json::to_value(&error).unwrap();
+    assert_eq!(
+        value,
+        json!({"type": "SyntaxError", "message": "Expected dot after parameter."})
+    );
+    let error: LambdaError = serde_json::from_value(value).unwrap();
+    assert_eq!(
+        error.to_string(),
+        "SyntaxError: Expected dot after parameter."
+    );
+}
+
+/// The Church numeral for `n`, built without parsing.
+fn numeral(n: usize) -> Expression {
+    let mut body = Expression::new_variable("x");
+    for _ in 0..n {
+        body = Expression::new_application(Expression::new_variable("f"), body);
+    }
+    Expression::new_function("f", Expression::new_function("x", body))
+}
+
+#[test]
+fn nesting_limit() {
+    let expr = numeral(124);
+    assert_eq!(Depth::of(&expr), 127);
+    let json = serde_json::to_string(&expr).unwrap();
+    assert_eq!(serde_json::from_str::<Expression>(&json).unwrap(), expr);
+
+    let json = serde_json::to_string(&numeral(125)).unwrap();
+    assert!(serde_json::from_str::<Expression>(&json).is_err());
+}
+
+#[test]
+fn flat_round_trip() {
+    for expr in TermGenerator::new(11).with_max_size(40).take(500) {
+        let json = serde_json::to_string(&FlatExpression(expr.clone())).unwrap();
+        let FlatExpression(read) = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, expr);
+    }
+
+    let expr = numeral(100_000);
+    let json = serde_json::to_string(&FlatExpression(expr.clone())).unwrap();
+    let FlatExpression(read) = serde_json::from_str(&json).unwrap();
+    assert_eq!(read, expr);
+}
+
+#[test]
+fn flat_format() {
+    let value = serde_json::to_value(FlatExpression(parse("(λx.x) y"))).unwrap();
+    assert_eq!(
+        value,
+        json!([
+            {"type": "variable", "name": "x"},
+            {"type": "function", "param": "x", "body": 0},
+            {"type": "variable", "name": "y"},
+            {"type": "application", "lhs": 1, "rhs": 2},
+        ])
+    );
+}
+
+#[test]
+fn flat_invalid() {
+    let invalid = [
+        // No nodes.
+        json!([]),
+        // A node refers to itself.
+        json!([{"type": "function", "param": "x", "body": 0}]),
+        // A node refers to a later one.
+        json!([
+            {"type": "function", "param": "x", "body": 1},
+            {"type": "variable", "name": "x"},
+        ]),
+        // A node is used twice.
+        json!([
+            {"type": "variable", "name": "x"},
+            {"type": "application", "lhs": 0, "rhs": 0},
+        ]),
+        // A node isn't used.
+        json!([
+            {"type": "variable", "name": "x"},
+            {"type": "variable", "name": "y"},
+        ]),
+    ];
+    for value in invalid {
+        assert!(
+            serde_json::from_value::<FlatExpression>(value.clone()).is_err(),
+            "{}",
+            value
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::{Expression, Label, Parts, Symbol};
+
+#[test]
+fn parse_string_labels() {
+    let expr: Expression<String> = "λx.x y".parse().expect("Failed to parse test code.");
+    let Expression::Function(param, _) = &expr else {
+        panic!("Expected a function, got {}", expr);
+    };
+    assert_eq!(param, "x");
+    assert_eq!(expr.to_string(), "λx.x y");
+}
+
+#[test]
+fn convert_between_labels() {
+    let expr: Expression = church::succ() * church::two();
+    let strings = Expression::<String>::from(expr.clone());
+    assert_eq!(strings.to_string(), expr.to_string());
+    assert_eq!(Expression::<Symbol>::from(strings), expr);
+}
+
+#[test]
+fn evaluate_integer_labels() {
+    // Number the variables of each expression by their symbol index.
+    let expr: Expression<u32> =
+        (church::add() * church::one() * church::two()).map_labels(|label: Symbol| label.index());
+    let expected: Expression<u32> = church::three().map_labels(|label: Symbol| label.index());
+    assert_eq!(expr.evaluate(), expected.normalize());
+}
+
+#[test]
+fn numbered_free_variables_are_not_captured() {
+    // λ0.1, with 1 free.
+    let expr: Expression<u32> = Expression::new_function(0u32, Expression::new_variable(1u32));
+    assert_eq!(expr.clone().normalize().to_string(), "λ2.1");
+    assert_eq!(expr.evaluate().to_string(), "λ2.1");
+}
+
+#[test]
+fn too_many_functions_for_label_type() {
+    let mut expr: Expression<u16> = Expression::new_variable(0u16);
+    for _ in 0..u16::MAX {
+        expr = Expression::new_function(0u16, expr);
+    }
+    // There are 65535 numbered labels, but 1 is taken by the free variable.
+    let expr = Expression::new_application(expr, Expression::new_variable(1u16));
+    let error = lambda::try_normalize_variables(expr.clone()).unwrap_err();
+    assert!(
+        error.to_string().contains("Too many functions"),
+        "{}",
+        error
+    );
+    assert!(lambda::evaluate_limited(expr, 10).is_err());
+}
+
+/// A label type without a default value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Name(Box<str>);
+
+impl Label for Name {
+    fn numbered(n: usize) -> Option<Self> {
+        Some(Name(n.to_string().into()))
+    }
+}
+
+#[test]
+fn labels_without_default() {
+    let expr: Expression<Name> = church::succ().map_labels(|label| Name(label.as_str().into()));
+    let two = (expr * church::one().map_labels(|label| Name(label.as_str().into()))).evaluate();
+    let Parts::Function(Name(param), body) = two.into_parts() else {
+        panic!("Expected a function");
+    };
+    assert_eq!(&*param, "1");
+    assert!(matches!(body, Expression::Function(_, _)));
+}
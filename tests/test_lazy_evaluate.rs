@@ -0,0 +1,61 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::evaluate_lazy;
+use lambda::Expression;
+
+fn check_same_as_evaluate(expr: Expression) {
+    assert_eq!(
+        evaluate_lazy(expr.clone()).to_string(),
+        expr.evaluate().to_string()
+    );
+}
+
+fn check_is_equal(code: &str, expected: &str) {
+    let expr = code
+        .parse::<Expression>()
+        .expect("Failed to parse test code.")
+        .evaluate_lazy();
+    let expected = expected
+        .parse::<Expression>()
+        .expect("Failed to parse expected test result.")
+        .normalize();
+    assert_eq!(expr.to_string(), expected.to_string());
+}
+
+#[test]
+fn simple_terms() {
+    check_is_equal("x", "x");
+    check_is_equal("λx.x", "λx.x");
+    check_is_equal("(λx.x) y", "y");
+    check_is_equal("(λx.x x) y", "y y");
+    check_is_equal("(λx.λy.y x) z", "λy.y z");
+    check_is_equal("((λx.λy.x y) (λx.x x)) (λx.x)", "λx.x");
+}
+
+#[test]
+fn unused_arguments_are_never_evaluated() {
+    check_is_equal("(λx.λy.y) ((λx.x x) (λx.x x))", "λy.y");
+}
+
+#[test]
+fn free_variables_are_not_captured() {
+    check_is_equal("(λx.λy.x) y", "λz.y");
+    check_is_equal("(λx.λy.x) 1", "λz.1");
+}
+
+#[test]
+fn church_arithmetic() {
+    check_same_as_evaluate(church::succ() * church::two());
+    check_same_as_evaluate(church::pred() * church::three());
+    check_same_as_evaluate(church::add() * church::one() * church::two());
+    check_same_as_evaluate(church::mul() * church::two() * church::three());
+    check_same_as_evaluate(church::pow() * church::two() * church::three());
+}
+
+#[test]
+fn shared_arguments() {
+    let four = church::pow() * church::two() * church::two();
+    let sixteen = church::pow() * church::two() * four;
+    check_same_as_evaluate(sixteen);
+}
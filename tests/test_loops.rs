@@ -0,0 +1,39 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::{evaluate_checked, Expression, LambdaError};
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+fn check_loops(code: &str) -> String {
+    match evaluate_checked(parse(code)) {
+        Err(LambdaError::RuntimeError(message)) => message,
+        result => panic!("Expected {} to loop, got {:?}", code, result),
+    }
+}
+
+#[test]
+fn omega() {
+    let message = check_loops("(λx.x x) (λx.x x)");
+    assert!(message.contains("after 1 β-reduction."), "{}", message);
+}
+
+#[test]
+fn loop_inside_term() {
+    check_loops("λy.y ((λx.x x) (λx.x x))");
+    check_loops("(λx.λy.y) ((λx.x x) (λx.x x))");
+}
+
+#[test]
+fn terminating_terms_are_evaluated() {
+    for expr in [
+        parse("(λx.x) ((λx.x) y)"),
+        parse("(λf.f (f y)) (λx.x)"),
+        church::pred() * church::three(),
+        church::pow() * church::two() * church::three(),
+    ] {
+        assert_eq!(evaluate_checked(expr.clone()).unwrap(), expr.evaluate());
+    }
+}
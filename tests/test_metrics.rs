@@ -0,0 +1,60 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::{Expression, Metrics};
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+#[test]
+fn variable() {
+    let metrics = parse("x").metrics();
+    assert_eq!(
+        metrics,
+        Metrics {
+            size: 1,
+            depth: 1,
+            ..Metrics::default()
+        }
+    );
+}
+
+#[test]
+fn counts() {
+    let expr = church::pred();
+    assert_eq!(expr.size(), 18);
+    assert_eq!(expr.depth(), 11);
+    assert_eq!(expr.abstractions(), 7);
+    assert_eq!(expr.applications(), 5);
+    assert_eq!(expr.redexes(), 0);
+    let metrics = expr.metrics();
+    assert_eq!(metrics.size, expr.size());
+    assert_eq!(metrics.depth, expr.depth());
+}
+
+#[test]
+fn redexes() {
+    assert_eq!(parse("(λx.x) y").redexes(), 1);
+    assert_eq!(parse("(λx.x) (λy.y) z").redexes(), 1);
+    assert_eq!(parse("x (λx.x)").redexes(), 0);
+    assert_eq!(parse("λx.(λy.y) ((λz.z) x)").redexes(), 2);
+    assert_eq!(
+        (church::mul() * church::two() * church::three()).redexes(),
+        1
+    );
+}
+
+#[test]
+fn evaluation_removes_redexes() {
+    let expr = church::pow() * church::two() * church::three();
+    assert_eq!(expr.evaluate().redexes(), 0);
+}
+
+#[test]
+fn display() {
+    assert_eq!(
+        parse("(λx.x) y").metrics().to_string(),
+        "size 4, depth 3, 1 abstractions, 1 applications, 1 redexes"
+    );
+}
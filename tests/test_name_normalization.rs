@@ -38,3 +38,9 @@ fn compound_expressions() {
         "((λ1.λ2.2 1) (λ3.λ4.3 (3 4))) (λ5.λ6.5 (5 6))",
     )
 }
+
+#[test]
+fn numbered_free_variables() {
+    check_normalized_correctly("λx.x 1", "λ2.2 1");
+    check_normalized_correctly("λx.λy.x 2 y 4", "λ1.λ3.((1 2) 3) 4");
+}
@@ -0,0 +1,71 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::evaluate_nbe;
+use lambda::Expression;
+
+fn check_same_as_evaluate(expr: Expression) {
+    assert_eq!(
+        evaluate_nbe(expr.clone()).to_string(),
+        expr.evaluate().to_string()
+    );
+}
+
+fn check_is_equal(code: &str, expected: &str) {
+    let expr = code
+        .parse::<Expression>()
+        .expect("Failed to parse test code.")
+        .evaluate_nbe();
+    let expected = expected
+        .parse::<Expression>()
+        .expect("Failed to parse expected test result.")
+        .normalize();
+    assert_eq!(expr.to_string(), expected.to_string());
+}
+
+#[test]
+fn simple_terms() {
+    check_is_equal("x", "x");
+    check_is_equal("λx.x", "λx.x");
+    check_is_equal("(λx.x) y", "y");
+    check_is_equal("(λx.x x) y", "y y");
+    check_is_equal("(λx.λy.y x) z", "λy.y z");
+    check_is_equal("((λx.λy.x y) (λx.x x)) (λx.x)", "λx.x");
+}
+
+#[test]
+fn free_variables_are_not_captured() {
+    check_is_equal("(λx.λy.x) y", "λz.y");
+    check_is_equal("(λf.λx.f x) (λy.x)", "λz.x");
+    check_is_equal("(λx.λy.x) 1", "λz.1");
+}
+
+#[test]
+fn nested_copies_of_functions() {
+    let code = "(λf.λx.f f) (λf.λg.(λx.(f g) z) (λz.λg.x))";
+    check_same_as_evaluate(code.parse().unwrap());
+    check_is_equal(code, "λa.λb.(b z) z");
+}
+
+#[test]
+fn church_arithmetic() {
+    check_same_as_evaluate(church::succ() * church::two());
+    check_same_as_evaluate(church::pred() * church::three());
+    check_same_as_evaluate(church::add() * church::one() * church::two());
+    check_same_as_evaluate(church::mul() * church::two() * church::three());
+    check_same_as_evaluate(church::pow() * church::two() * church::three());
+}
+
+#[test]
+fn agrees_with_other_evaluators() {
+    let exprs = [
+        church::pred() * (church::mul() * church::three() * church::two()),
+        church::pow() * church::three() * church::two(),
+        church::two() * church::two() * church::two(),
+    ];
+    for expr in exprs {
+        let expected = evaluate_nbe(expr.clone());
+        assert_eq!(lambda::evaluate_lazy(expr.clone()), expected);
+        assert_eq!(lambda::evaluate_optimal(expr).unwrap(), expected);
+    }
+}
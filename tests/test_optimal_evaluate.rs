@@ -0,0 +1,70 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::evaluate_optimal;
+use lambda::Expression;
+
+fn check_same_as_evaluate(expr: Expression) {
+    let optimal = evaluate_optimal(expr.clone()).expect("Failed to read back net.");
+    assert_eq!(optimal.to_string(), expr.evaluate().to_string());
+}
+
+fn check_is_equal(code: &str, expected: &str) {
+    let expr = code
+        .parse::<Expression>()
+        .expect("Failed to parse test code.");
+    let expected = expected
+        .parse::<Expression>()
+        .expect("Failed to parse expected test result.")
+        .normalize();
+    let result = evaluate_optimal(expr).expect("Failed to read back net.");
+    assert_eq!(result.to_string(), expected.to_string());
+}
+
+#[test]
+fn simple_terms() {
+    check_is_equal("x", "x");
+    check_is_equal("λx.x", "λx.x");
+    check_is_equal("(λx.x) y", "y");
+    check_is_equal("(λx.x x) y", "y y");
+    check_is_equal("(λx.λy.y x) z", "λy.y z");
+    check_is_equal("(λx.λy.x) 1", "λz.1");
+    check_is_equal("((λx.λy.x y) (λx.x x)) (λx.x)", "λx.x");
+}
+
+#[test]
+fn unused_arguments_are_never_evaluated() {
+    check_is_equal("(λx.λy.y) ((λx.x x) (λx.x x))", "λy.y");
+}
+
+#[test]
+fn duplicated_functions() {
+    check_is_equal("(λf.f (f x)) (λy.g y y)", "g (g x x) (g x x)");
+    check_is_equal("(λf.λx.f (f x)) (λy.λz.y z)", "λx.λz.x z");
+}
+
+#[test]
+fn church_arithmetic() {
+    check_same_as_evaluate(church::succ() * church::two());
+    check_same_as_evaluate(church::pred() * church::three());
+    check_same_as_evaluate(church::add() * church::one() * church::two());
+    check_same_as_evaluate(church::mul() * church::two() * church::three());
+    check_same_as_evaluate(church::pow() * church::two() * church::three());
+    check_same_as_evaluate(church::pow() * church::three() * church::two());
+}
+
+#[test]
+fn shared_arguments() {
+    let four = church::pow() * church::two() * church::two();
+    let sixteen = church::pow() * church::two() * four;
+    check_same_as_evaluate(sixteen);
+}
+
+#[test]
+fn self_application_of_shared_terms() {
+    check_same_as_evaluate(church::two() * church::two() * church::two());
+    check_is_equal(
+        &format!("(λx.x x) ({})", church::TWO),
+        "λf.λx.f (f (f (f x)))",
+    );
+}
@@ -49,3 +49,10 @@ fn combined_expressions() {
         "g ((λx.g (x x)) (λx.g (x x)))",
     );
 }
+
+#[test]
+fn multiple_parameters() {
+    check_parsed_correctly("λx y.x", "λx.λy.x");
+    check_parsed_correctly(r"\f x.f (f x)", "λf.λx.f (f x)");
+    check_parsed_correctly("(λx y z.x z (y z)) u", "(λx.λy.λz.(x z) (y z)) u");
+}
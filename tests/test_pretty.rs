@@ -0,0 +1,114 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::{Expression, PrettyOptions};
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+fn terms() -> Vec<Expression> {
+    let mut terms: Vec<Expression> = [
+        "x",
+        "f λx.x",
+        "(λx.x) y",
+        "x (y z) (λu.u) w",
+        "λx.(λy.y x) (λz.z) x",
+        "(λh.(λx.h (x x)) (λx.h (x x))) g",
+        "λx.x (λy.λz.y) (λw.w w) (u v)",
+    ]
+    .into_iter()
+    .map(parse)
+    .collect();
+    terms.extend([
+        church::zero(),
+        church::succ(),
+        church::pred(),
+        church::add(),
+        church::mul(),
+        church::pow(),
+        church::pow() * church::two() * church::three(),
+    ]);
+    terms
+}
+
+fn all_options() -> Vec<PrettyOptions> {
+    let mut options = Vec::new();
+    for ascii in [false, true] {
+        for minimal_parentheses in [false, true] {
+            for multi_parameter in [false, true] {
+                for width in [None, Some(1), Some(12), Some(40)] {
+                    options.push(PrettyOptions {
+                        ascii,
+                        minimal_parentheses,
+                        multi_parameter,
+                        width,
+                        ..PrettyOptions::default()
+                    });
+                }
+            }
+        }
+    }
+    options
+}
+
+#[test]
+fn minimal_parentheses() {
+    let options = PrettyOptions::default();
+    assert_eq!(parse("(x y) z").pretty(&options), "x y z");
+    assert_eq!(parse("x (y z)").pretty(&options), "x (y z)");
+    assert_eq!(parse("(λx.x) y").pretty(&options), "(λx.x) y");
+    assert_eq!(parse("f (λx.x)").pretty(&options), "f λx.x");
+    assert_eq!(parse("f (λx.x) y").pretty(&options), "f (λx.x) y");
+    assert_eq!(parse("λx.(λy.y) x").pretty(&options), "λx.(λy.y) x");
+}
+
+#[test]
+fn display_parentheses() {
+    let options = PrettyOptions {
+        minimal_parentheses: false,
+        ..PrettyOptions::default()
+    };
+    for expr in terms() {
+        assert_eq!(expr.pretty(&options), expr.to_string());
+    }
+}
+
+#[test]
+fn ascii_and_multiple_parameters() {
+    let options = PrettyOptions {
+        ascii: true,
+        multi_parameter: true,
+        ..PrettyOptions::default()
+    };
+    assert_eq!(church::pow().pretty(&options), r"\b e.e b");
+    assert_eq!(parse("λx.f (λy.λz.y)").pretty(&options), r"\x.f \y z.y");
+    assert_eq!(parse("λx.(λy.y) x").pretty(&options), r"\x.(\y.y) x");
+}
+
+#[test]
+fn line_width() {
+    let options = PrettyOptions {
+        width: Some(16),
+        ..PrettyOptions::default()
+    };
+    assert_eq!(parse("λx.x").pretty(&options), "λx.x");
+    assert_eq!(
+        parse("λf.λx.f (f (f x))").pretty(&options),
+        "λf.\n  λx.f (f (f x))"
+    );
+    assert_eq!(
+        parse("function argument other").pretty(&options),
+        "function\n  argument\n  other"
+    );
+}
+
+#[test]
+fn round_trip() {
+    for options in all_options() {
+        for expr in terms() {
+            let code = expr.pretty(&options);
+            assert_eq!(parse(&code), expr, "{:?} printed as {}", options, code);
+        }
+    }
+}
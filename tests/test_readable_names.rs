@@ -0,0 +1,66 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::testing::TermGenerator;
+use lambda::{
+    alpha_equivalent, evaluate_limited, evaluate_readable, evaluate_readable_limited,
+    readable_names, Expression,
+};
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+#[test]
+fn restores_original_names() {
+    let expr = church::add() * church::two() * church::three();
+    assert_eq!(
+        evaluate_readable(expr).to_string(),
+        "λf.λx.f (f (f (f (f x))))"
+    );
+    let expr = parse("(λy.λz.z y) (λa.a)");
+    assert_eq!(evaluate_readable(expr).to_string(), "λz.z (λa.a)");
+}
+
+#[test]
+fn avoids_capture() {
+    let expr = parse("(λx.λy.x y) y");
+    assert_eq!(evaluate_readable(expr).to_string(), "λy'.y y'");
+    let expr = parse("(λf.λx.f x) (λy.λx.x y)");
+    assert_eq!(evaluate_readable(expr).to_string(), "λx.λx'.x' x");
+    let expr = parse("(λx.λy.x y) 1");
+    assert_eq!(evaluate_readable(expr).to_string(), "λy.1 y");
+}
+
+#[test]
+fn numbered_names() {
+    assert_eq!(readable_names(parse("λ1.λ2.2 1")).to_string(), "λa.λb.b a");
+    assert_eq!(readable_names(parse("λ1.a 1")).to_string(), "λb.a b");
+}
+
+#[test]
+fn primed_names_parse() {
+    let expr = parse("λx.λx.x");
+    let readable = readable_names(expr.clone());
+    assert_eq!(readable.to_string(), "λx.λx'.x'");
+    assert_eq!(parse(&readable.to_string()), readable);
+}
+
+#[test]
+fn equivalent_to_evaluate() {
+    for expr in TermGenerator::new(8).with_max_size(15).take(500) {
+        // Skip terms that take too many steps, and limit the readable evaluation the same way,
+        // so that neither can run forever.
+        let Ok(expected) = evaluate_limited(expr.clone(), 1000) else {
+            continue;
+        };
+        let readable = evaluate_readable_limited(expr, 1000).unwrap();
+        assert!(
+            alpha_equivalent(&readable, &expected),
+            "{} vs {}",
+            readable,
+            expected
+        );
+        assert_eq!(readable_names(readable.clone()), readable);
+    }
+}
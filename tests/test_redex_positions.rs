@@ -0,0 +1,95 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::testing::TermGenerator;
+use lambda::Direction::{Body, Lhs, Rhs};
+use lambda::{alpha_equivalent, Expression};
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+#[test]
+fn positions() {
+    assert!(parse("λx.x y").redex_positions().is_empty());
+    assert_eq!(parse("(λx.x) y").redex_positions(), [vec![]]);
+    // Outer redexes come before the redexes inside them, and lhs before rhs.
+    assert_eq!(
+        parse("(λx.(λy.y) x) ((λz.z) w) (λv.(λu.u) v)").redex_positions(),
+        [
+            vec![Lhs],
+            vec![Lhs, Lhs, Body],
+            vec![Lhs, Rhs],
+            vec![Rhs, Body]
+        ]
+    );
+}
+
+#[test]
+fn subexpressions() {
+    let expr = parse("(λx.(λy.y) x) ((λz.z) w)");
+    let redexes: Vec<String> = expr
+        .redex_positions()
+        .iter()
+        .map(|path| expr.subexpression(path).unwrap().to_string())
+        .collect();
+    assert_eq!(
+        redexes,
+        ["(λx.(λy.y) x) ((λz.z) w)", "(λy.y) x", "(λz.z) w"]
+    );
+    assert_eq!(expr.subexpression(&[]), Some(&expr));
+    assert_eq!(expr.subexpression(&[Body]), None);
+    assert_eq!(expr.subexpression(&[Rhs, Rhs, Rhs]), None);
+}
+
+#[test]
+fn contraction() {
+    let expr = parse("(λx.(λy.y) x) ((λz.z) w)");
+    let reducts: Vec<String> = expr
+        .redex_positions()
+        .iter()
+        .map(|path| expr.clone().contract_at(path).unwrap().to_string())
+        .collect();
+    assert_eq!(
+        reducts,
+        ["(λy.y) ((λz.z) w)", "(λx.x) ((λz.z) w)", "(λx.(λy.y) x) w"]
+    );
+    // Only redexes can be contracted.
+    assert_eq!(expr.clone().contract_at(&[Lhs]), None);
+    assert_eq!(expr.clone().contract_at(&[Rhs, Rhs]), None);
+    assert_eq!(expr.contract_at(&[Body]), None);
+}
+
+#[test]
+fn capture() {
+    // The function is renamed so that the free y stays free.
+    let reduced = parse("λy.(λx.λy.x) y").contract_at(&[Body]).unwrap();
+    assert!(alpha_equivalent(&reduced, &parse("λy.λz.y")));
+}
+
+#[test]
+fn innermost_first() {
+    // Contracting the last redex each time is an applicative order reduction.
+    let mut expr = church::mul() * church::two() * church::three();
+    while let Some(path) = expr.redex_positions().pop() {
+        expr = expr.contract_at(&path).unwrap();
+    }
+    let evaluated = (church::mul() * church::two() * church::three()).evaluate();
+    assert!(alpha_equivalent(&expr, &evaluated));
+}
+
+#[test]
+fn trace_and_graph_positions() {
+    for expr in TermGenerator::new(50).with_max_size(10).take(100) {
+        let trace = expr.clone().trace(20);
+        for (term, path) in trace.terms().iter().zip(trace.redex_positions()) {
+            assert_eq!(Some(path), term.redex_positions().first(), "{}", expr);
+        }
+
+        let graph = expr.reduction_graph(30);
+        for (from, path, to) in graph.reductions() {
+            let reduced = graph.terms()[from].clone().contract_at(path).unwrap();
+            assert!(alpha_equivalent(&reduced, &graph.terms()[to]));
+        }
+    }
+}
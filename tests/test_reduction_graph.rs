@@ -0,0 +1,130 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::testing::TermGenerator;
+use lambda::{alpha_equivalent, Expression};
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+fn strings(terms: &[Expression]) -> Vec<String> {
+    terms.iter().map(|term| term.to_string()).collect()
+}
+
+#[test]
+fn normal_form() {
+    let graph = parse("λx.x").reduction_graph(10);
+    assert_eq!(strings(graph.terms()), ["λx.x"]);
+    assert_eq!(graph.edges().count(), 0);
+    assert_eq!(graph.normal_forms().collect::<Vec<_>>(), [0]);
+    assert!(graph.is_complete());
+}
+
+#[test]
+fn cycles() {
+    let graph = parse("(λx.x x) (λx.x x)").reduction_graph(100);
+    assert_eq!(graph.terms().len(), 1);
+    assert_eq!(graph.edges().collect::<Vec<_>>(), [(0, 0)]);
+    assert_eq!(graph.normal_forms().count(), 0);
+    assert!(graph.is_complete());
+
+    // Normal order reaches the normal form, and other orders go around in circles.
+    let graph = parse("(λx.λy.y) ((λx.x x) (λx.x x))").reduction_graph(100);
+    assert_eq!(
+        strings(graph.terms()),
+        ["(λx.λy.y) ((λx.x x) (λx.x x))", "λy.y"]
+    );
+    assert_eq!(graph.edges().collect::<Vec<_>>(), [(0, 1), (0, 0)]);
+    assert_eq!(graph.normal_forms().collect::<Vec<_>>(), [1]);
+}
+
+#[test]
+fn alpha_equivalent_terms() {
+    // Both redexes reduce to λa.a, under different names.
+    let graph = parse("(λx.λy.y) ((λz.z) (λa.a))").reduction_graph(100);
+    assert_eq!(graph.terms().len(), 3);
+    assert_eq!(graph.edges().collect::<Vec<_>>(), [(0, 1), (0, 2), (2, 1)]);
+    assert_eq!(graph.index_of(&parse("λb.b")), Some(1));
+    assert_eq!(graph.index_of(&parse("λb.c")), None);
+}
+
+#[test]
+fn max_size() {
+    // Each step adds another copy of the function, of size 6.
+    let graph = parse("(λx.x x x) (λx.x x x)").reduction_graph(30);
+    assert!(!graph.is_complete());
+    assert_eq!(
+        strings(graph.terms()),
+        [
+            "(λx.(x x) x) (λx.(x x) x)",
+            "((λx.(x x) x) (λx.(x x) x)) (λx.(x x) x)",
+            "(((λx.(x x) x) (λx.(x x) x)) (λx.(x x) x)) (λx.(x x) x)",
+        ]
+    );
+    assert_eq!(
+        parse("(λx.x x x) (λx.x x x)")
+            .reduction_graph(1)
+            .terms()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn church_rosser() {
+    let graph = (church::add() * church::one() * church::one()).reduction_graph(100);
+    assert!(graph.is_complete());
+    let normal_forms: Vec<usize> = graph.normal_forms().collect();
+    assert_eq!(normal_forms.len(), 1);
+    assert!(alpha_equivalent(
+        &graph.terms()[normal_forms[0]],
+        &church::two()
+    ));
+    // Every term reduces to the normal form.
+    assert!(graph.terms().iter().all(|term| {
+        let reduced = term.clone().reduction_graph(100);
+        reduced.index_of(&church::two()).is_some()
+    }));
+}
+
+#[test]
+fn generated_terms() {
+    for expr in TermGenerator::new(49).with_max_size(12).take(200) {
+        let graph = expr.clone().reduction_graph(40);
+        assert!(alpha_equivalent(&graph.terms()[0], &expr));
+        let normal_forms: Vec<usize> = graph.normal_forms().collect();
+        assert!(normal_forms.len() <= 1, "{}", expr);
+        // Normal order finds the normal form whenever there is one.
+        let trace = expr.clone().trace(1000);
+        if let (Some(&i), true) = (normal_forms.first(), graph.is_complete()) {
+            let normal_form = trace.normal_form().expect("normal order terminates");
+            assert!(alpha_equivalent(normal_form, &graph.terms()[i]), "{}", expr);
+        }
+    }
+}
+
+#[test]
+fn dot() {
+    let graph = parse("(λx.x x) ((λy.y) z)").reduction_graph(100);
+    assert_eq!(
+        graph.to_dot(),
+        r#"digraph {
+    node [shape=box];
+    n0 [label="(λx.x x) ((λy.y) z)"];
+    n1 [label="((λy.y) z) ((λy.y) z)"];
+    n2 [label="(λx.x x) z"];
+    n3 [label="z ((λy.y) z)"];
+    n4 [label="((λy.y) z) z"];
+    n5 [label="z z", peripheries=2];
+    n0 -> n1;
+    n0 -> n2;
+    n1 -> n3;
+    n1 -> n4;
+    n2 -> n5;
+    n3 -> n5;
+    n4 -> n5;
+}
+"#
+    );
+}
@@ -0,0 +1,164 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::testing::TermGenerator;
+use lambda::{Expression, Highlight};
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+#[test]
+fn latex_terms() {
+    assert_eq!(parse("x").to_latex(), "x");
+    assert_eq!(
+        church::succ().to_latex(),
+        r"\lambda n.\,\lambda f.\,\lambda x.\,f\;(n\;f\;x)"
+    );
+    assert_eq!(parse("f (λx.x) y").to_latex(), r"f\;(\lambda x.\,x)\;y");
+    assert_eq!(
+        parse("λx'.foo x'").to_latex(),
+        r"\lambda x'.\,\mathit{foo}\;x'"
+    );
+}
+
+#[test]
+fn latex_special_characters() {
+    // Free de Bruijn indices are named like this by `TermArena::to_expression`.
+    let expr: Expression = Expression::new_function("x", Expression::new_variable("#0"));
+    assert_eq!(expr.to_latex(), r"\lambda x.\,\mathit{\#0}");
+    let expr: Expression = Expression::new_variable(r"a\b{c}&d%e$f_g^h~i");
+    assert_eq!(
+        expr.to_latex(),
+        r"\mathit{a\backslash{}b\{c\}\&d\%e\$f\_g\text{\textasciicircum}h\text{\textasciitilde}i}"
+    );
+}
+
+#[test]
+fn latex_trace() {
+    let trace = parse("(λx.x) ((λy.y) z)").trace(10);
+    let lines = [
+        r"\begin{align*}",
+        r"  & \underline{(\lambda x.\,x)\;((\lambda y.\,y)\;z)} \\",
+        r"  \to_\beta\; & \underline{(\lambda y.\,y)\;z} \\",
+        r"  \to_\beta\; & z",
+        r"\end{align*}",
+    ];
+    assert_eq!(trace.to_latex(), lines.join("\n") + "\n");
+}
+
+#[test]
+fn latex_incomplete_trace() {
+    let trace = parse("f ((λx.x x) (λx.x x))").trace(1);
+    let lines = [
+        r"\begin{align*}",
+        r"  & f\;(\underline{(\lambda x.\,x\;x)\;\lambda x.\,x\;x}) \\",
+        r"  \to_\beta\; & f\;((\lambda x.\,x\;x)\;\lambda x.\,x\;x) \\",
+        r"  \to_\beta\; & \cdots",
+        r"\end{align*}",
+    ];
+    assert_eq!(trace.to_latex(), lines.join("\n") + "\n");
+}
+
+#[test]
+fn html_terms() {
+    assert_eq!(
+        parse("(λx.x) y").to_html(),
+        concat!(
+            r#"<span class="lambda-application">("#,
+            r#"<span class="lambda-function">λ<span class="lambda-parameter">x</span>."#,
+            r#"<span class="lambda-variable">x</span></span>) "#,
+            r#"<span class="lambda-variable">y</span></span>"#,
+        )
+    );
+}
+
+#[test]
+fn html_escapes_labels() {
+    let expr: Expression<String> = Expression::new_variable("<b>&");
+    assert_eq!(
+        expr.to_html(),
+        r#"<span class="lambda-variable">&lt;b&gt;&amp;</span>"#
+    );
+}
+
+#[test]
+fn html_trace() {
+    let trace = parse("f ((λx.x) y)").trace(10);
+    assert_eq!(
+        trace.to_html(),
+        concat!(
+            "<ol class=\"lambda-trace\">\n",
+            r#"<li><span class="lambda-application"><span class="lambda-variable">f</span> "#,
+            r#"(<span class="lambda-application lambda-redex">("#,
+            r#"<span class="lambda-function">λ<span class="lambda-parameter">x</span>."#,
+            r#"<span class="lambda-variable">x</span></span>) "#,
+            r#"<span class="lambda-variable">y</span></span>)</span></li>"#,
+            "\n",
+            r#"<li><span class="lambda-application"><span class="lambda-variable">f</span> "#,
+            r#"<span class="lambda-variable">y</span></span></li>"#,
+            "\n</ol>\n"
+        )
+    );
+}
+
+#[test]
+fn text_matches_display() {
+    for expr in TermGenerator::new(11).with_max_size(30).take(200) {
+        let trace = expr.clone().trace(0);
+        let text = trace.to_text(Highlight::None);
+        let expected = if trace.is_complete() {
+            format!("  {}\n", expr)
+        } else {
+            format!("  {}\n→ …\n", expr)
+        };
+        assert_eq!(text, expected);
+    }
+}
+
+#[test]
+fn text_markers() {
+    let trace = parse("(λf.f ((λx.x) a)) (λy.y)").trace(10);
+    assert_eq!(
+        trace.to_text(Highlight::Markers),
+        concat!(
+            "  (λf.f ((λx.x) a)) (λy.y)\n",
+            "  ^^^^^^^^^^^^^^^^^^^^^^^^\n",
+            "→ (λy.y) ((λx.x) a)\n",
+            "  ~~~~~~~~~~~~~~~~~\n",
+            "  ^^^^^^^^^^^^^^^^^\n",
+            "→ (λx.x) a\n",
+            "  ~~~~~~~~\n",
+            "  ^^^^^^^^\n",
+            "→ a\n",
+            "  ~\n",
+        )
+    );
+}
+
+#[test]
+fn text_markers_inside_term() {
+    let trace = parse("λz.z ((λx.x x) z)").trace(10);
+    assert_eq!(
+        trace.to_text(Highlight::Markers),
+        concat!(
+            "  λz.z ((λx.x x) z)\n",
+            "        ^^^^^^^^^^\n",
+            "→ λz.z (z z)\n",
+            "        ~~~\n",
+        )
+    );
+}
+
+#[test]
+fn text_color() {
+    let trace = parse("f ((λx.x) ((λy.y) z))").trace(10);
+    assert_eq!(
+        trace.to_text(Highlight::Color),
+        concat!(
+            "  f (\x1b[1;31m(λx.x) ((λy.y) z)\x1b[0m)\n",
+            "→ f (\x1b[32m\x1b[1;31m(λy.y) z\x1b[0m)\n",
+            "→ f \x1b[32mz\x1b[0m\n",
+        )
+    );
+}
@@ -0,0 +1,78 @@
+use pretty_assertions::assert_eq;
+
+use lambda::testing::TermGenerator;
+use lambda::{Expression, PrettyOptions};
+
+const TERMS: usize = 2_000;
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse printed term.")
+}
+
+#[test]
+fn generated_sizes() {
+    let mut generator = TermGenerator::new(1);
+    for size in 1..50 {
+        assert_eq!(generator.generate_sized(size).size(), size);
+    }
+    assert!(TermGenerator::new(2)
+        .with_max_size(10)
+        .take(TERMS)
+        .all(|expr| expr.size() <= 10));
+}
+
+#[test]
+fn same_seed_same_terms() {
+    let first: Vec<Expression> = TermGenerator::new(3).take(100).collect();
+    let second: Vec<Expression> = TermGenerator::new(3).take(100).collect();
+    let other: Vec<Expression> = TermGenerator::new(4).take(100).collect();
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+}
+
+#[test]
+fn variable_pool() {
+    for expr in TermGenerator::new(5).with_variables(["a", "b"]).take(TERMS) {
+        let mut labels = expr
+            .free_variables()
+            .into_iter()
+            .chain(expr.bound_variables());
+        assert!(labels.all(|label| label == "a" || label == "b"));
+    }
+}
+
+#[test]
+fn display() {
+    for expr in TermGenerator::new(6).with_max_size(40).take(TERMS) {
+        assert_eq!(parse(&expr.to_string()), expr);
+    }
+}
+
+#[test]
+fn pretty() {
+    let options = [
+        PrettyOptions::default(),
+        PrettyOptions {
+            ascii: true,
+            multi_parameter: true,
+            ..PrettyOptions::default()
+        },
+        PrettyOptions {
+            minimal_parentheses: false,
+            width: Some(10),
+            ..PrettyOptions::default()
+        },
+        PrettyOptions {
+            multi_parameter: true,
+            width: Some(20),
+            indent: 4,
+            ..PrettyOptions::default()
+        },
+    ];
+    for expr in TermGenerator::new(7).with_max_size(40).take(TERMS) {
+        for options in &options {
+            let code = expr.pretty(options);
+            assert_eq!(parse(&code), expr, "{:?} printed as {}", options, code);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::{evaluate_with_stats, Expression};
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+#[test]
+fn same_result_as_evaluate() {
+    for expr in [
+        parse("(λx.λy.x) a b"),
+        church::pred() * church::three(),
+        church::pow() * church::two() * church::three(),
+    ] {
+        let (result, _) = evaluate_with_stats(expr.clone());
+        assert_eq!(result, expr.evaluate());
+    }
+}
+
+#[test]
+fn no_work_for_normal_forms() {
+    let (_, stats) = evaluate_with_stats(church::pred());
+    assert_eq!(stats.beta_steps, 0);
+    assert_eq!(stats.substitutions, 0);
+    assert_eq!(stats.max_size, church::pred().size());
+}
+
+#[test]
+fn counts() {
+    let (_, stats) = parse("(λx.x x) y").evaluate_with_stats();
+    assert_eq!(stats.beta_steps, 1);
+    assert_eq!(stats.substitutions, 2);
+    assert_eq!(stats.max_size, 6);
+}
+
+#[test]
+fn compare_encodings() {
+    let (_, add) = (church::add() * church::one() * church::two()).evaluate_with_stats();
+    let (_, succ) = (church::succ() * (church::succ() * church::one())).evaluate_with_stats();
+    assert!(add.beta_steps > 0);
+    assert!(add.max_size >= church::add().size());
+    assert_eq!(succ.beta_steps, 6);
+}
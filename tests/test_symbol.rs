@@ -0,0 +1,36 @@
+use pretty_assertions::assert_eq;
+
+use lambda::{Expression, Symbol};
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+#[test]
+fn parsed_names_are_interned() {
+    let expr = parse("λfoo.foo bar");
+    let Expression::Function(param, body) = &expr else {
+        panic!("Expected a function, got {}", expr);
+    };
+    assert_eq!(*param, Symbol::new("foo"));
+    assert_eq!(Symbol::get("bar").map(Symbol::as_str), Some("bar"));
+    assert_eq!(body.to_string(), "foo bar");
+}
+
+#[test]
+fn numbered_names() {
+    let expr = parse("λx.λy.x y").normalize();
+    let Expression::Function(param, _) = &expr else {
+        panic!("Expected a function, got {}", expr);
+    };
+    assert_eq!(*param, Symbol::number(1));
+    assert_eq!(Symbol::number(2).as_str(), "2");
+}
+
+#[test]
+fn ordered_by_name() {
+    let b = Symbol::new("symbol_order_b");
+    let a = Symbol::new("symbol_order_a");
+    assert!(a < b);
+    assert_eq!(a.to_string(), "symbol_order_a");
+}
@@ -0,0 +1,90 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::testing::TermGenerator;
+use lambda::{
+    alpha_equivalent, evaluate_cancellable, reduce_normal_order, CancellationToken, Expression,
+};
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+fn strings(expr: Expression, max_steps: usize) -> Vec<String> {
+    let trace = expr.trace(max_steps);
+    trace.terms().iter().map(|term| term.to_string()).collect()
+}
+
+#[test]
+fn normal_form() {
+    let trace = parse("x").trace(10);
+    assert_eq!(trace.steps(), 0);
+    assert!(trace.is_complete());
+    assert_eq!(trace.normal_form(), Some(&parse("x")));
+}
+
+#[test]
+fn normal_order() {
+    assert_eq!(
+        strings(parse("(λx.λy.y) ((λx.x x) (λx.x x))"), 10),
+        ["(λx.λy.y) ((λx.x x) (λx.x x))", "λy.y"]
+    );
+    assert_eq!(
+        strings(parse("(λf.f ((λx.x) a)) (λy.y)"), 10),
+        [
+            "(λf.f ((λx.x) a)) (λy.y)",
+            "(λy.y) ((λx.x) a)",
+            "(λx.x) a",
+            "a"
+        ]
+    );
+}
+
+#[test]
+fn max_steps() {
+    let omega = parse("(λx.x x) (λx.x x)");
+    let trace = omega.clone().trace(5);
+    assert_eq!(trace.steps(), 5);
+    assert!(!trace.is_complete());
+    assert_eq!(trace.normal_form(), None);
+    assert!(trace.terms().iter().all(|term| *term == omega));
+}
+
+#[test]
+fn reduce_without_tracing() {
+    for expr in TermGenerator::new(10).with_max_size(15).take(200) {
+        let trace = expr.clone().trace(100);
+        match trace.normal_form() {
+            Some(normal_form) => assert_eq!(&reduce_normal_order(expr, 100).unwrap(), normal_form),
+            None => assert!(reduce_normal_order(expr, 100).is_err()),
+        }
+    }
+}
+
+#[test]
+fn avoids_capture() {
+    assert_eq!(
+        strings(parse("(λx.λy.x y) y"), 10),
+        ["(λx.λy.x y) y", "λ1.y 1"]
+    );
+}
+
+#[test]
+fn same_normal_form_as_evaluate() {
+    let trace = (church::mul() * church::two() * church::three()).trace(1000);
+    assert_eq!(trace.steps(), 7);
+    assert!(alpha_equivalent(
+        trace.normal_form().unwrap(),
+        &parse("λf.λx.f (f (f (f (f (f x)))))")
+    ));
+
+    for expr in TermGenerator::new(9).with_max_size(15).take(500) {
+        let trace = expr.clone().trace(200);
+        let Some(normal_form) = trace.normal_form() else {
+            continue;
+        };
+        let token = CancellationToken::new();
+        let expected = evaluate_cancellable(expr, &token).unwrap();
+        assert!(alpha_equivalent(normal_form, &expected));
+    }
+}
@@ -0,0 +1,39 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::Expression;
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+#[test]
+fn free_variables() {
+    assert_eq!(parse("x").free_variables(), ["x"]);
+    assert_eq!(parse("λx.x").free_variables(), Vec::<&str>::new());
+    assert_eq!(parse("(λx.x y) x").free_variables(), ["y", "x"]);
+    assert_eq!(parse("λx.λy.z (λz.z x) z").free_variables(), ["z"]);
+}
+
+#[test]
+fn bound_variables() {
+    assert_eq!(parse("x").bound_variables(), Vec::<&str>::new());
+    assert_eq!(parse("(λx.x y) (λy.λx.x)").bound_variables(), ["x", "y"]);
+}
+
+#[test]
+fn closed_terms() {
+    for expr in [church::zero(), church::pred(), church::pow()] {
+        assert!(expr.is_closed());
+    }
+    assert!(!parse("λx.y").is_closed());
+    assert!(!parse("(λx.x) x").is_closed());
+}
+
+#[test]
+fn occurrences() {
+    let expr = parse("x (λx.x x) (λy.x) x");
+    assert_eq!(expr.occurrences("x"), 3);
+    assert_eq!(expr.occurrences("y"), 0);
+    assert_eq!(church::two().occurrences("f"), 0);
+}
@@ -0,0 +1,53 @@
+use pretty_assertions::assert_eq;
+
+use lambda::church;
+use lambda::{BoundVariables, Depth, Expression, FreeVariables, Size, Symbol, VisitorMut};
+
+fn parse(code: &str) -> Expression {
+    code.parse().expect("Failed to parse test code.")
+}
+
+#[test]
+fn size_and_depth() {
+    assert_eq!(Size::of(&parse("x")), 1);
+    assert_eq!(Depth::of(&parse("x")), 1);
+    assert_eq!(Size::of(&church::two()), 7);
+    assert_eq!(Depth::of(&church::two()), 5);
+    assert_eq!(Depth::of(&parse("(x y) (λz.z)")), 3);
+}
+
+#[test]
+fn free_and_bound_variables() {
+    let expr = parse("λx.x y (λy.y w) (λw.x)");
+    assert_eq!(FreeVariables::of(&expr), ["y", "w"]);
+    assert_eq!(BoundVariables::of(&expr), ["x", "y", "w"]);
+    assert!(FreeVariables::of(&church::pow()).is_empty());
+}
+
+#[test]
+fn fold_matches_visitors() {
+    let expr = church::pred();
+    let size = expr.fold(|_| 1, |_, body| body + 1, |lhs, rhs| lhs + rhs + 1);
+    assert_eq!(size, Size::of(&expr));
+    let depth = expr.fold(|_| 1, |_, body| body + 1, |lhs, rhs| lhs.max(rhs) + 1);
+    assert_eq!(depth, Depth::of(&expr));
+}
+
+#[test]
+fn rename_parameters() {
+    struct Rename;
+
+    impl VisitorMut<Symbol> for Rename {
+        fn visit_variable(&mut self, label: &mut Symbol) {
+            *label = Symbol::new(&label.as_str().to_uppercase());
+        }
+
+        fn enter_function(&mut self, param: &mut Symbol) {
+            *param = Symbol::new(&param.as_str().to_uppercase());
+        }
+    }
+
+    let mut expr = parse("λx.x (λy.y z)");
+    expr.visit_mut(&mut Rename);
+    assert_eq!(expr, parse("λX.X (λY.Y Z)"));
+}